@@ -1,36 +1,82 @@
 extern crate sdl2;
 
 use core::default::Default;
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::Keycode;
 use sdl2::libc::printf;
-use ugui::control::{Button, Control, Listbox, Scrollbar, Textbox};
-use ugui::geo::Point;
-use ugui::input::Input;
+use sdl2::mouse::{Cursor, SystemCursor};
+use std::path::Path;
+use ugui::control::{
+    Button, ButtonMode, Control, Listbox, MessageBoxButtons, MessageBoxResult, Orientation,
+    Scrollbar, SortDirection, Splitter, SplitterPane, Table, TableColumn, Textbox, TreeModel,
+    TreeView,
+};
+use ugui::output::{CursorIcon, FrameOutput};
+use ugui::sdl_input::InputCollector;
+use ugui::sdl_painter::SdlPainter;
 use ugui::standard_styler::StandardStyler;
 use ugui::*;
 
+// A tree of numbers where every node has ten children, so it's effectively infinite
+struct NumberTree;
+
+impl TreeModel for NumberTree {
+    fn children(&self, node: Option<u64>) -> Vec<u64> {
+        let base = node.map_or(1, |x| x * 10);
+        (base..base + 10).collect()
+    }
+
+    fn label(&self, node: u64) -> String {
+        format!("Node {}", node)
+    }
+
+    // The nodes never change
+    fn revision(&self) -> Option<u64> {
+        Some(0)
+    }
+}
+
+fn system_cursor(cursor: CursorIcon) -> SystemCursor {
+    match cursor {
+        CursorIcon::Default => SystemCursor::Arrow,
+        CursorIcon::Text => SystemCursor::IBeam,
+        CursorIcon::ResizeHorizontal => SystemCursor::SizeWE,
+        CursorIcon::ResizeVertical => SystemCursor::SizeNS,
+        CursorIcon::ResizeNwSe => SystemCursor::SizeNWSE,
+        CursorIcon::ResizeNeSw => SystemCursor::SizeNESW,
+    }
+}
+
 pub fn main() -> Result<(), String> {
     let sdl_context = sdl2::init()?;
     let video_subsystem = sdl_context.video()?;
     let ttf_context = sdl2::ttf::init().map_err(|e| e.to_string())?;
 
     let window = video_subsystem
-        .window("Test", 640, 480)
+        .window("Test", 1040, 480)
         .opengl()
         .resizable()
         .build()
         .map_err(|e| e.to_string())?;
 
-    let canvas = window.into_canvas().build().map_err(|e| e.to_string())?;
+    // Animations request frames back to back, so they're paced by the display instead of spinning
+    let canvas = window
+        .into_canvas()
+        .present_vsync()
+        .build()
+        .map_err(|e| e.to_string())?;
 
     let mut event_pump = sdl_context.event_pump().map_err(|e| e.to_string())?;
 
-    let mut ugui = Ugui {
-        styler: StandardStyler::new(canvas, &ttf_context),
-        persistent_state: PersistentState::new(),
-    };
+    let painter = SdlPainter::new(canvas, &ttf_context, Path::new("examples/fonts/segoe.ttf"))?;
+    let mut ugui = Ugui::new(StandardStyler::new(painter));
+    ugui.set_viewport_size(geo::Size::new(1040.0, 480.0));
+    let mut input_collector = InputCollector::new(video_subsystem.text_input());
+    let mut cursor_icon = CursorIcon::Default;
+    // SDL only refers to the cursor which is set, so it has to be kept alive here
+    let mut _cursor = Cursor::from_system(system_cursor(cursor_icon))?;
+    _cursor.set();
 
-    let mut mouse_wheel = 0;
     let mut value = 0.0;
     let mut enabled = true;
     let mut index = Some(0);
@@ -67,65 +113,251 @@ pub fn main() -> Result<(), String> {
         "Item C",
     ];
 
+    let columns = vec![
+        TableColumn {
+            header: "Name",
+            width: 120.0,
+        },
+        TableColumn {
+            header: "Size",
+            width: 60.0,
+        },
+        TableColumn {
+            header: "Description",
+            width: 160.0,
+        },
+    ];
+    let mut rows = vec![
+        vec!["segoe.ttf", "1024", "Font used by the standard styler"],
+        vec!["main.rs", "4", "Example application"],
+        vec!["lib.rs", "12", "Core immediate-mode logic"],
+        vec!["geo.rs", "3", "Geometry primitives"],
+    ];
+    let mut table_index = None;
+    let mut table_sort = None;
+    let mut tree_selected = None;
+    let mut counter = 0;
+    let mut window_open = true;
+    let mut confirming_close = false;
+    let mut window_rect = geo::Rect::new(700.0, 200.0, 240.0, 160.0);
+
+    let mut output = FrameOutput::default();
     'running: loop {
-        for event in event_pump.poll_iter() {
+        // Nothing changes on screen without new events, unless ugui asked for another frame
+        let first_event = match output.repaint_at {
+            _ if output.repaint => None,
+            Some(time) => event_pump
+                .wait_event_timeout(input_collector.time_until(time).as_millis() as u32 + 1),
+            None => Some(event_pump.wait_event()),
+        };
+        for event in first_event.into_iter().chain(event_pump.poll_iter()) {
+            input_collector.process_event(&event);
             match event {
                 Event::Quit { .. } => break 'running,
-                Event::MouseWheel { y, .. } => {
-                    mouse_wheel = y as i8;
-                }
+                Event::Window {
+                    win_event: WindowEvent::Exposed,
+                    ..
+                } => ugui.request_repaint(),
+                Event::Window {
+                    win_event: WindowEvent::SizeChanged(w, h),
+                    ..
+                } => ugui.set_viewport_size(geo::Size::new(w as f32, h as f32)),
+                // F1 to F4 switch between common display scales, F12 toggles the debug overlay
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } => match keycode {
+                    Keycode::F1 => ugui.set_scale(1.0),
+                    Keycode::F2 => ugui.set_scale(1.25),
+                    Keycode::F3 => ugui.set_scale(1.5),
+                    Keycode::F4 => ugui.set_scale(2.0),
+                    Keycode::F12 => ugui.set_debug_overlay(!ugui.debug_overlay()),
+                    _ => {}
+                },
                 _ => {}
             }
         }
 
-        ugui.begin(Input {
-            mouse_position: Point {
-                x: event_pump.mouse_state().x() as f32,
-                y: event_pump.mouse_state().y() as f32,
-            },
-            mouse_wheel,
-            primary_down: event_pump.mouse_state().left(),
-        });
-        mouse_wheel = 0;
+        let input = input_collector.take_input();
+        if !ugui.needs_frame(&input) {
+            continue;
+        }
+        ugui.begin(input);
 
-        if ugui.button(
-            Control {
-                uid: 0,
-                enabled: true,
-                rect: geo::Rect::new(60.0, 30.0, 100.0, 23.0),
-                ..Default::default()
-            },
-            Button {
-                text: &index.unwrap().to_string(),
-            },
-        ) {
+        if ugui
+            .button(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect: geo::Rect::new(60.0, 30.0, 100.0, 23.0),
+                    ..Default::default()
+                },
+                Button {
+                    text: &index.unwrap().to_string(),
+                    ..Default::default()
+                },
+            )
+            .clicked
+        {
             enabled ^= true;
         }
 
-        index = ugui.listbox(
+        index = ugui
+            .listbox(
+                Control {
+                    uid: 10,
+                    enabled,
+                    rect: geo::Rect::new(60.0, 80.0, 200.0, 350.0),
+                    ..Default::default()
+                },
+                Listbox {
+                    items: &items,
+                    index,
+                },
+            )
+            .value;
+
+        // The textbox and table share their space, and the table can be hidden by double-clicking the divider
+        let panes = ugui
+            .splitter(
+                Control {
+                    uid: 30,
+                    enabled,
+                    rect: geo::Rect::new(300.0, 50.0, 300.0, 380.0),
+                },
+                Splitter {
+                    orientation: Orientation::Vertical,
+                    min_sizes: [60.0, 60.0],
+                    collapsible: Some(SplitterPane::Second),
+                    ..Default::default()
+                },
+            )
+            .value;
+
+        text = ugui
+            .textbox(
+                Control {
+                    uid: 15,
+                    enabled,
+                    rect: panes.first,
+                    ..Default::default()
+                },
+                Textbox { text: &text },
+            )
+            .value;
+
+        let table_response = ugui.table(
             Control {
-                uid: 10,
+                uid: 20,
                 enabled,
-                rect: geo::Rect::new(60.0, 80.0, 200.0, 350.0),
-                ..Default::default()
+                rect: panes.second,
             },
-            Listbox {
-                items: &items,
-                index,
+            Table {
+                columns: &columns,
+                rows: &rows,
+                index: table_index,
+                sort: table_sort,
             },
         );
+        let (new_table_index, new_table_sort) = table_response.value;
+        table_index = new_table_index;
 
-        text = ugui.textbox(
-            Control {
-                uid: 15,
-                enabled,
-                rect: geo::Rect::new(300.0, 50.0, 300.0, 200.0),
-                ..Default::default()
-            },
-            Textbox { text: &text },
-        );
+        if new_table_sort != table_sort {
+            if let Some(sort) = new_table_sort {
+                rows.sort_by(|a, b| match sort.direction {
+                    SortDirection::Ascending => a[sort.column].cmp(b[sort.column]),
+                    SortDirection::Descending => b[sort.column].cmp(a[sort.column]),
+                });
+                table_index = None;
+            }
+            table_sort = new_table_sort;
+        }
 
-        ugui.end();
+        tree_selected = ugui
+            .tree_view(
+                Control {
+                    uid: 40,
+                    enabled,
+                    rect: geo::Rect::new(620.0, 50.0, 200.0, 380.0),
+                },
+                TreeView {
+                    model: &NumberTree,
+                    selected: tree_selected,
+                },
+            )
+            .value;
+
+        ugui.begin_scroll_area(Control {
+            uid: 50,
+            enabled,
+            rect: geo::Rect::new(840.0, 50.0, 180.0, 380.0),
+        });
+        for i in 0..20 {
+            // These buttons count presses, so they fire as soon as they are pushed down
+            if ugui
+                .button(
+                    Control {
+                        uid: 60 + i,
+                        enabled: true,
+                        rect: geo::Rect::new(10.0, 10.0 + i as f32 * 30.0, 240.0, 23.0),
+                    },
+                    Button {
+                        text: &format!("Scrolled button {} ({})", i, counter),
+                        mode: ButtonMode::Press,
+                    },
+                )
+                .clicked
+            {
+                counter += 1;
+            }
+        }
+        ugui.end_scroll_area();
+
+        if window_open {
+            confirming_close |= ugui
+                .begin_window(100, "Floating window", &mut window_rect)
+                .value
+                .close_clicked;
+            if ugui
+                .button(
+                    Control {
+                        uid: 103,
+                        enabled: true,
+                        rect: geo::Rect::new(10.0, 10.0, 160.0, 23.0),
+                    },
+                    Button {
+                        text: &format!("Counted {} presses", counter),
+                        ..Default::default()
+                    },
+                )
+                .clicked
+            {
+                counter += 1;
+            }
+            ugui.end_window();
+        }
+
+        if confirming_close {
+            if let Some(result) = ugui.message_box(
+                110,
+                "Close window",
+                "Do you want to close the floating window?",
+                MessageBoxButtons::YesNoCancel,
+            ) {
+                confirming_close = false;
+                window_open = result != MessageBoxResult::Yes;
+            }
+        }
+
+        output = ugui.end();
+        input_collector.apply_output(&output);
+        if output.cursor != cursor_icon {
+            // The old cursor may only be freed once it's not in use anymore
+            let new_cursor = Cursor::from_system(system_cursor(output.cursor))?;
+            new_cursor.set();
+            _cursor = new_cursor;
+            cursor_icon = output.cursor;
+        }
     }
 
     Ok(())
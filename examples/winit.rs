@@ -0,0 +1,192 @@
+use std::rc::Rc;
+#[cfg(feature = "accesskit_winit")]
+use ugui::accesskit_adapter::WinitAccessAdapter;
+use ugui::control::{Button, Control, Textbox};
+use ugui::geo::{Rect, Size};
+use ugui::input::Input;
+use ugui::output::FrameOutput;
+use ugui::standard_styler::StandardStyler;
+use ugui::winit_input::InputCollector;
+use ugui::winit_painter::WinitPainter;
+use ugui::Ugui;
+use winit::application::ApplicationHandler;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy};
+use winit::window::{Window, WindowId};
+
+// Screen readers talk to the app through events sent by the AccessKit adapter
+#[cfg(feature = "accesskit_winit")]
+type UserEvent = accesskit_winit::Event;
+#[cfg(not(feature = "accesskit_winit"))]
+type UserEvent = ();
+
+struct App {
+    font_data: Vec<u8>,
+    window: Option<Rc<Window>>,
+    ugui: Option<Ugui<StandardStyler<WinitPainter>>>,
+    input_collector: InputCollector,
+    // Input which was taken to check whether a frame is needed, and is waiting for the redraw
+    pending_input: Option<Input>,
+    output: FrameOutput,
+    #[cfg_attr(not(feature = "accesskit_winit"), allow(dead_code))]
+    proxy: EventLoopProxy<UserEvent>,
+    #[cfg(feature = "accesskit_winit")]
+    access_adapter: Option<WinitAccessAdapter>,
+    text: String,
+    counter: i32,
+}
+
+impl App {
+    fn frame(&mut self) {
+        let (Some(window), Some(ugui)) = (&self.window, &mut self.ugui) else {
+            return;
+        };
+
+        let input = self
+            .pending_input
+            .take()
+            .unwrap_or_else(|| self.input_collector.take_input());
+        ugui.begin(input);
+
+        if ugui
+            .button(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect: Rect::new(20.0, 20.0, 160.0, 23.0),
+                },
+                Button {
+                    text: &format!("Clicked {} times", self.counter),
+                    ..Default::default()
+                },
+            )
+            .clicked
+        {
+            self.counter += 1;
+        }
+
+        self.text = ugui
+            .textbox(
+                Control {
+                    uid: 1,
+                    enabled: true,
+                    rect: Rect::new(20.0, 60.0, 300.0, 120.0),
+                },
+                Textbox { text: &self.text },
+            )
+            .value;
+
+        self.output = ugui.end();
+        #[cfg(feature = "accesskit_winit")]
+        if let Some(access_adapter) = &mut self.access_adapter {
+            access_adapter.update(ugui);
+        }
+        self.input_collector.apply_output(window, &self.output);
+    }
+}
+
+impl ApplicationHandler<UserEvent> for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_some() {
+            return;
+        }
+
+        // The window stays hidden until the accessibility adapter is set up
+        let window = Rc::new(
+            event_loop
+                .create_window(
+                    Window::default_attributes()
+                        .with_title("Test")
+                        .with_visible(false),
+                )
+                .unwrap(),
+        );
+        #[cfg(feature = "accesskit_winit")]
+        {
+            self.access_adapter = Some(WinitAccessAdapter::new(
+                event_loop,
+                &window,
+                self.proxy.clone(),
+            ));
+        }
+        window.set_visible(true);
+        let painter = WinitPainter::new(window.clone(), &self.font_data).unwrap();
+        let mut ugui = Ugui::new(StandardStyler::new(painter));
+        ugui.set_scale(window.scale_factor() as f32);
+        let size = window.inner_size();
+        ugui.set_viewport_size(Size::new(size.width as f32, size.height as f32));
+
+        self.window = Some(window);
+        self.ugui = Some(ugui);
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+        #[cfg(feature = "accesskit_winit")]
+        if let (Some(window), Some(access_adapter)) = (&self.window, &mut self.access_adapter) {
+            access_adapter.process_event(window, &event);
+        }
+        self.input_collector.process_event(&event);
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                if let Some(ugui) = &mut self.ugui {
+                    ugui.set_scale(scale_factor as f32);
+                }
+            }
+            WindowEvent::Resized(size) => {
+                if let Some(ugui) = &mut self.ugui {
+                    ugui.set_viewport_size(Size::new(size.width as f32, size.height as f32));
+                }
+            }
+            WindowEvent::RedrawRequested => self.frame(),
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "accesskit_winit")]
+    fn user_event(&mut self, _: &ActiveEventLoop, event: UserEvent) {
+        if let (Some(ugui), Some(access_adapter)) = (&mut self.ugui, &mut self.access_adapter) {
+            access_adapter.handle_event(ugui, event.window_event);
+        }
+    }
+
+    // Frames only run when something changed, otherwise the loop sleeps until the next event or the time ugui asked for
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let (Some(window), Some(ugui)) = (&self.window, &self.ugui) else {
+            return;
+        };
+        let input = self.input_collector.take_input();
+        if ugui.needs_frame(&input) {
+            self.pending_input = Some(input);
+            event_loop.set_control_flow(ControlFlow::Wait);
+            window.request_redraw();
+        } else if let Some(time) = self.output.repaint_at {
+            event_loop.set_control_flow(ControlFlow::wait_duration(
+                self.input_collector.time_until(time),
+            ));
+        } else {
+            event_loop.set_control_flow(ControlFlow::Wait);
+        }
+    }
+}
+
+pub fn main() -> Result<(), String> {
+    let event_loop = EventLoop::<UserEvent>::with_user_event()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut app = App {
+        font_data: std::fs::read("examples/fonts/segoe.ttf").map_err(|e| e.to_string())?,
+        window: None,
+        ugui: None,
+        input_collector: InputCollector::new(),
+        pending_input: None,
+        output: FrameOutput::default(),
+        proxy: event_loop.create_proxy(),
+        #[cfg(feature = "accesskit_winit")]
+        access_adapter: None,
+        text: "Type here".to_string(),
+        counter: 0,
+    };
+    event_loop.run_app(&mut app).map_err(|e| e.to_string())
+}
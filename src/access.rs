@@ -0,0 +1,42 @@
+use crate::geo::Rect;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AccessRole {
+    Label,
+    Button,
+    Scrollbar,
+    Listbox,
+    Textbox,
+    Table,
+    TreeView,
+    Window,
+    Splitter,
+}
+
+// What assistive technology gets to know about a control, collected while accessibility is enabled
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessNode {
+    pub uid: i64,
+    pub role: AccessRole,
+    pub name: String,
+    // Text of the control's value, e.g. the selected item of a listbox
+    pub value: Option<String>,
+    // Value of controls with a range, which goes from 0 to 1
+    pub numeric_value: Option<f32>,
+    pub enabled: bool,
+    pub focused: bool,
+    // The part of the control which isn't clipped away, in logical units
+    pub bounds: Rect,
+    // The window or modal the control was placed in, whose node it belongs to
+    pub window: Option<i64>,
+}
+
+// A request from assistive technology, which is applied to its control during the next frame
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccessAction {
+    Press,
+    Focus,
+    // Replaces the text of textboxes, selects the listbox item with the text or sets scrollbars and splitters to the number
+    SetValue(String),
+}
@@ -0,0 +1,199 @@
+use crate::access::{AccessAction, AccessNode, AccessRole};
+use crate::styler::Styler;
+use crate::Ugui;
+use accesskit::{
+    Action, ActionData, ActionRequest, Node, NodeId, Rect, Role, TreeId, TreeInfo, TreeUpdate,
+};
+
+// Nodes are identified by the uid of their control, so the root takes the one of a uid which is left out of the tree
+const ROOT_UID: i64 = i64::MIN;
+const ROOT_ID: NodeId = NodeId(ROOT_UID as u64);
+
+fn node_id(uid: i64) -> NodeId {
+    NodeId(uid as u64)
+}
+
+fn to_accesskit_node(access_node: &AccessNode, scale: f32) -> Node {
+    let role = match access_node.role {
+        AccessRole::Label => Role::Label,
+        AccessRole::Button => Role::Button,
+        AccessRole::Scrollbar => Role::ScrollBar,
+        AccessRole::Listbox => Role::ListBox,
+        AccessRole::Textbox => Role::MultilineTextInput,
+        AccessRole::Table => Role::Table,
+        AccessRole::TreeView => Role::Tree,
+        AccessRole::Window => Role::Window,
+        AccessRole::Splitter => Role::Splitter,
+    };
+    let mut node = Node::new(role);
+
+    let bounds = access_node.bounds;
+    node.set_bounds(Rect::new(
+        (bounds.x * scale) as f64,
+        (bounds.y * scale) as f64,
+        (bounds.right() * scale) as f64,
+        (bounds.bottom() * scale) as f64,
+    ));
+    if !access_node.name.is_empty() {
+        node.set_label(access_node.name.as_str());
+    }
+    if let Some(value) = &access_node.value {
+        node.set_value(value.as_str());
+    }
+    if let Some(value) = access_node.numeric_value {
+        node.set_numeric_value(value as f64);
+        node.set_min_numeric_value(0.0);
+        node.set_max_numeric_value(1.0);
+    }
+    if !access_node.enabled {
+        node.set_disabled();
+        return node;
+    }
+
+    // Only offer what the control handles when receiving the action
+    match access_node.role {
+        AccessRole::Button => {
+            node.add_action(Action::Click);
+            node.add_action(Action::Focus);
+        }
+        AccessRole::Textbox => {
+            node.add_action(Action::Focus);
+            node.add_action(Action::SetValue);
+        }
+        AccessRole::Scrollbar | AccessRole::Listbox | AccessRole::Splitter => {
+            node.add_action(Action::SetValue)
+        }
+        AccessRole::TreeView => node.add_action(Action::Focus),
+        AccessRole::Label | AccessRole::Table | AccessRole::Window => {}
+    }
+    node
+}
+
+// Builds a full tree from the controls of the last frame, with bounds in physical pixels
+// Controls placed in windows are nested under the window's node, and all others under the root
+// Uid i64::MIN is reserved for the root node, so a control with it isn't part of the tree
+pub fn tree_update<T: Styler>(ugui: &Ugui<T>) -> TreeUpdate {
+    let access_nodes: Vec<&AccessNode> = ugui
+        .access_nodes()
+        .iter()
+        .filter(|x| x.uid != ROOT_UID)
+        .collect();
+    let children_of = |window: Option<i64>| -> Vec<NodeId> {
+        access_nodes
+            .iter()
+            .filter(|x| x.window == window)
+            .map(|x| node_id(x.uid))
+            .collect()
+    };
+
+    // Controls of windows which didn't get a node are kept in the tree by moving them up to the root
+    let mut root = Node::new(Role::Window);
+    let mut root_children = children_of(None);
+    root_children.extend(
+        access_nodes
+            .iter()
+            .filter(|x| {
+                x.window
+                    .is_some_and(|window| !access_nodes.iter().any(|y| y.uid == window))
+            })
+            .map(|x| node_id(x.uid)),
+    );
+    root.set_children(root_children);
+
+    let mut nodes = vec![(ROOT_ID, root)];
+    nodes.extend(access_nodes.iter().map(|x| {
+        let mut node = to_accesskit_node(x, ugui.scale());
+        if x.role == AccessRole::Window {
+            node.set_children(children_of(Some(x.uid)));
+        }
+        (node_id(x.uid), node)
+    }));
+
+    TreeUpdate {
+        nodes,
+        tree: Some(TreeInfo::new(ROOT_ID)),
+        tree_id: TreeId::ROOT,
+        focus: access_nodes
+            .iter()
+            .find(|x| x.focused)
+            .map_or(ROOT_ID, |x| node_id(x.uid)),
+    }
+}
+
+// Gets the uid and action to pass to Ugui::queue_access_action, if ugui can handle the request
+pub fn action_from_request(request: &ActionRequest) -> Option<(i64, AccessAction)> {
+    if request.target_node == ROOT_ID {
+        return None;
+    }
+    let action = match (request.action, &request.data) {
+        (Action::Click, _) => AccessAction::Press,
+        (Action::Focus, _) => AccessAction::Focus,
+        (Action::SetValue, Some(ActionData::Value(value))) => {
+            AccessAction::SetValue(value.to_string())
+        }
+        (Action::SetValue, Some(ActionData::NumericValue(value))) => {
+            AccessAction::SetValue(value.to_string())
+        }
+        _ => return None,
+    };
+    Some((request.target_node.0 as i64, action))
+}
+
+// Publishes the accessibility tree of a winit window
+// Accessibility is enabled on the ugui once assistive technology asks for the tree
+#[cfg(feature = "accesskit_winit")]
+pub struct WinitAccessAdapter {
+    adapter: accesskit_winit::Adapter,
+}
+
+#[cfg(feature = "accesskit_winit")]
+impl WinitAccessAdapter {
+    // Has to be created before the window is shown for the first time
+    // Events the adapter sends through the proxy need to be passed to handle_event
+    pub fn new<E: From<accesskit_winit::Event> + Send + 'static>(
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        window: &winit::window::Window,
+        proxy: winit::event_loop::EventLoopProxy<E>,
+    ) -> WinitAccessAdapter {
+        WinitAccessAdapter {
+            adapter: accesskit_winit::Adapter::with_event_loop_proxy(event_loop, window, proxy),
+        }
+    }
+
+    // Has to be called with every window event before ugui sees it
+    pub fn process_event(
+        &mut self,
+        window: &winit::window::Window,
+        event: &winit::event::WindowEvent,
+    ) {
+        self.adapter.process_event(window, event);
+    }
+
+    pub fn handle_event<T: Styler>(
+        &mut self,
+        ugui: &mut Ugui<T>,
+        event: accesskit_winit::WindowEvent,
+    ) {
+        match event {
+            accesskit_winit::WindowEvent::InitialTreeRequested => {
+                ugui.set_accessibility(true);
+                self.adapter.update_if_active(|| tree_update(ugui));
+            }
+            accesskit_winit::WindowEvent::ActionRequested(request) => {
+                if let Some((uid, action)) = action_from_request(&request) {
+                    ugui.queue_access_action(uid, action);
+                }
+            }
+            accesskit_winit::WindowEvent::AccessibilityDeactivated => {
+                ugui.set_accessibility(false);
+            }
+        }
+    }
+
+    // Publishes the tree of the frame which just ended
+    pub fn update<T: Styler>(&mut self, ugui: &Ugui<T>) {
+        if ugui.accessibility() {
+            self.adapter.update_if_active(|| tree_update(ugui));
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::geo::Point;
+use crate::painter::Color;
+use std::collections::{HashMap, HashSet};
+
+// How long fading from one color to another takes, in seconds
+const FADE_DURATION: f32 = 0.15;
+// How quickly smooth scrolling closes the distance to its target, as a rate per second
+const SCROLL_RATE: f32 = 20.0;
+// Smooth scrolling snaps to its target once it's this close, in the 0 to 1 range of scroll values
+const SCROLL_SNAP_DISTANCE: f32 = 0.001;
+// How long the caret stays shown or hidden while blinking, in seconds
+const CARET_BLINK_INTERVAL: f64 = 0.5;
+
+#[derive(Copy, Clone)]
+struct Fade {
+    from: Color,
+    to: Color,
+    // Goes from 0 at the start of the fade to 1 once it's done
+    progress: f32,
+}
+
+impl Fade {
+    fn current(&self) -> Color {
+        self.from.lerp(self.to, self.progress)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Caret {
+    index: usize,
+    // When the caret got to the index, which restarts the blinking so it's shown right away
+    since: f64,
+}
+
+// Values which change over time instead of snapping to their targets, for stylers to use while drawing
+// State is keyed by the uid of the control it belongs to, and dropped for controls which weren't drawn during a frame
+#[derive(Default)]
+pub struct Animations {
+    time: f64,
+    delta: f32,
+    // Controls with several faded colors tell them apart by slot
+    fades: HashMap<(i64, usize), Fade>,
+    carets: HashMap<i64, Caret>,
+    drawn: HashSet<i64>,
+    repaint_at: Option<f64>,
+}
+
+impl Animations {
+    // Advances all animations by the time since the last frame, in seconds
+    pub fn begin(&mut self, time: f64, delta: f32) {
+        self.time = time;
+        self.delta = delta;
+        self.drawn.clear();
+        self.repaint_at = None;
+    }
+
+    pub fn end(&mut self) {
+        let drawn = &self.drawn;
+        self.fades.retain(|x, _| drawn.contains(&x.0));
+        self.carets.retain(|x, _| drawn.contains(x));
+    }
+
+    // Gets the time of the input clock at which the animations change next, which is now while any of them is running
+    pub fn repaint_at(&self) -> Option<f64> {
+        self.repaint_at
+    }
+
+    fn request_repaint(&mut self, time: f64) {
+        self.repaint_at = Some(self.repaint_at.map_or(time, |x| x.min(time)));
+    }
+
+    // Fades from the color the slot had before to the target, starting over whenever the target changes
+    pub fn fade(&mut self, uid: i64, slot: usize, target: Color) -> Color {
+        self.drawn.insert(uid);
+        let fade = self.fades.entry((uid, slot)).or_insert(Fade {
+            from: target,
+            to: target,
+            progress: 1.0,
+        });
+
+        if fade.to != target {
+            *fade = Fade {
+                from: fade.current(),
+                to: target,
+                progress: 0.0,
+            };
+        } else {
+            fade.progress = (fade.progress + self.delta / FADE_DURATION).min(1.0);
+        }
+
+        let fade = *fade;
+        if fade.progress < 1.0 {
+            self.request_repaint(self.time);
+        }
+        fade.current()
+    }
+
+    // Whether the caret is shown, which blinks for as long as it stays at the same index
+    pub fn caret_visible(&mut self, uid: i64, index: usize) -> bool {
+        self.drawn.insert(uid);
+        let time = self.time;
+        let caret = self
+            .carets
+            .entry(uid)
+            .or_insert(Caret { index, since: time });
+        if caret.index != index {
+            *caret = Caret { index, since: time };
+        }
+
+        let intervals = ((time - caret.since) / CARET_BLINK_INTERVAL).floor();
+        let next_blink = caret.since + (intervals + 1.0) * CARET_BLINK_INTERVAL;
+        self.request_repaint(next_blink);
+        (intervals as u64).is_multiple_of(2)
+    }
+}
+
+// Moves a scroll value towards the target over the time since the last frame, slowing down as it gets closer
+pub fn smooth_scroll(current: Point, target: Point, delta: f32) -> Point {
+    let scroll = current + (target - current) * (1.0 - (-delta * SCROLL_RATE).exp());
+    if scroll.dist(target) < SCROLL_SNAP_DISTANCE {
+        return target;
+    }
+    scroll
+}
@@ -1,6 +1,6 @@
 use crate::geo::Rect;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Control {
     pub uid: i64,
     pub enabled: bool,
@@ -17,9 +17,25 @@ impl Default for Control {
     }
 }
 
-#[derive(Copy, Clone)]
+// When a button reports being clicked
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ButtonMode {
+    // Once the mouse is released over the button, so the click can be cancelled by moving away
+    #[default]
+    Release,
+    // As soon as the mouse is pushed down, which is useful for buttons repeating an action
+    Press,
+}
+
+#[derive(Copy, Clone, Default)]
 pub struct Button<'a> {
     pub text: &'a str,
+    pub mode: ButtonMode,
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct Label<'a> {
+    pub text: &'a str,
 }
 
 #[derive(Copy, Clone)]
@@ -38,3 +54,183 @@ pub struct Listbox<'a> {
 pub struct Textbox<'a> {
     pub text: &'a String,
 }
+
+#[derive(Copy, Clone)]
+pub struct TableColumn<'a> {
+    pub header: &'a str,
+    pub width: f32,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TableSort {
+    pub column: usize,
+    pub direction: SortDirection,
+}
+
+#[derive(Copy, Clone)]
+pub struct Table<'a> {
+    pub columns: &'a Vec<TableColumn<'a>>,
+    pub rows: &'a Vec<Vec<&'a str>>,
+    pub index: Option<usize>,
+    pub sort: Option<TableSort>,
+}
+
+// The part of a table located under a point
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TableHit {
+    Header(usize),
+    Divider(usize),
+    Row(usize),
+    None,
+}
+
+pub trait TreeModel {
+    // Gets the children of a node, or the root nodes if no node is specified
+    fn children(&self, node: Option<u64>) -> Vec<u64>;
+    fn label(&self, node: u64) -> String;
+    fn has_children(&self, node: u64) -> bool {
+        !self.children(Some(node)).is_empty()
+    }
+    // Changes whenever nodes are added or removed, so tree views count their rows again
+    // Without a revision, tree views can't tell whether the model changed and count all rows every frame
+    fn revision(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct TreeView<'a> {
+    pub model: &'a dyn TreeModel,
+    pub selected: Option<u64>,
+}
+
+// A row of a tree view which isn't hidden inside a collapsed node
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeRow {
+    pub index: usize,
+    pub node: u64,
+    pub label: String,
+    pub depth: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+}
+
+// The part of a tree view located under a point
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TreeHit {
+    Expander(usize),
+    Row(usize),
+    None,
+}
+
+#[derive(Copy, Clone)]
+pub struct Window<'a> {
+    pub title: &'a str,
+    // Only the title bar of collapsed windows is shown
+    pub collapsed: bool,
+}
+
+// What happened to a window during the frame
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct WindowStatus {
+    pub collapsed: bool,
+    // The close button was clicked, so the window shouldn't be placed anymore
+    pub close_clicked: bool,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MessageBoxButtons {
+    Ok,
+    OkCancel,
+    YesNoCancel,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MessageBoxResult {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+}
+
+impl MessageBoxButtons {
+    // Gets the choices in the order their buttons are shown, along with the buttons' text
+    pub fn choices(&self) -> &'static [(MessageBoxResult, &'static str)] {
+        match self {
+            MessageBoxButtons::Ok => &[(MessageBoxResult::Ok, "OK")],
+            MessageBoxButtons::OkCancel => &[
+                (MessageBoxResult::Ok, "OK"),
+                (MessageBoxResult::Cancel, "Cancel"),
+            ],
+            MessageBoxButtons::YesNoCancel => &[
+                (MessageBoxResult::Yes, "Yes"),
+                (MessageBoxResult::No, "No"),
+                (MessageBoxResult::Cancel, "Cancel"),
+            ],
+        }
+    }
+
+    // Gets the choice made by closing the message box without clicking any of its buttons
+    pub fn dismissed(&self) -> MessageBoxResult {
+        match self {
+            MessageBoxButtons::Ok => MessageBoxResult::Ok,
+            _ => MessageBoxResult::Cancel,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Orientation {
+    // The panes are placed next to each other, with a vertical divider between them
+    #[default]
+    Horizontal,
+    // The panes are placed above each other, with a horizontal divider between them
+    Vertical,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SplitterPane {
+    First,
+    Second,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Splitter {
+    pub orientation: Orientation,
+    // Share of the space taken up by the first pane, until the divider was moved for the first time
+    pub ratio: f32,
+    // Limits of the first and second pane's size along the orientation
+    pub min_sizes: [f32; 2],
+    pub max_sizes: [f32; 2],
+    // The pane which is hidden and shown again by double-clicking the divider, if any
+    pub collapsible: Option<SplitterPane>,
+}
+
+impl Default for Splitter {
+    fn default() -> Self {
+        Splitter {
+            orientation: Default::default(),
+            ratio: 0.5,
+            min_sizes: [0.0; 2],
+            max_sizes: [f32::INFINITY; 2],
+            collapsible: None,
+        }
+    }
+}
+
+// Where the splitter put its panes, which are left to be filled by the caller
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SplitterPanes {
+    pub first: Rect,
+    pub second: Rect,
+    pub divider: Rect,
+    // Share of the space taken up by the first pane, which isn't affected by collapsing
+    pub ratio: f32,
+    pub collapsed: Option<SplitterPane>,
+}
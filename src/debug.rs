@@ -0,0 +1,33 @@
+use crate::geo::Rect;
+
+// How much drawing the styler did during a frame, not counting the debug overlay itself
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DrawStats {
+    // All primitives sent to the painter, including text
+    pub draw_calls: u32,
+    pub text_renders: u32,
+}
+
+// A control which built a response this frame
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DebugControl {
+    pub uid: i64,
+    // The part of the control which isn't clipped away, in screen space
+    pub rect: Rect,
+    pub enabled: bool,
+    // Whether another control with the same uid was placed this frame, which makes them share state
+    pub collides: bool,
+}
+
+// Everything the debug overlay shows, collected over a frame and handed to the styler before it ends
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DebugOverlay {
+    // In the order the controls were placed
+    pub controls: Vec<DebugControl>,
+    pub active_control: Option<i64>,
+    pub hovered_control: Option<i64>,
+    pub focused_control: Option<i64>,
+    // The persistent state of the hovered control, formatted with Debug
+    pub hovered_state: Option<String>,
+    pub draw_stats: DrawStats,
+}
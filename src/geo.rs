@@ -1,37 +1,122 @@
-use sdl2::rect::Point as SdlPoint;
-use sdl2::rect::Rect as SdlRect;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: f32,
     pub y: f32,
 }
 
 impl Point {
-    pub fn to_sdl(&self) -> SdlPoint {
-        SdlPoint::new(self.x as i32, self.y as i32)
-    }
     pub fn inside(&self, rect: Rect) -> bool {
+        self.inside_exclusive(rect)
+    }
+    // Checks whether the point is inside the rect, counting points on its edges as inside
+    pub fn inside_inclusive(&self, rect: Rect) -> bool {
+        self.x >= rect.x && self.x <= rect.right() && self.y >= rect.y && self.y <= rect.bottom()
+    }
+    // Checks whether the point is inside the rect, counting points on its edges as outside
+    pub fn inside_exclusive(&self, rect: Rect) -> bool {
         self.x > rect.x && self.x < rect.right() && self.y > rect.y && self.y < rect.bottom()
     }
-    pub fn add(&self, other: Point) -> Point {
+    pub fn dist(&self, other: Point) -> f32 {
+        ((other.x - self.x).powf(2.0) + (other.y - self.y).powf(2.0)).sqrt()
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
         Point {
             x: self.x + other.x,
             y: self.y + other.y,
         }
     }
-    pub fn sub(&self, other: Point) -> Point {
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
         Point {
             x: self.x - other.x,
             y: self.y - other.y,
         }
     }
-    pub fn dist(&self, other: Point) -> f32 {
-        ((other.x - self.x).powf(2.0) + (other.y - self.y).powf(2.0)).sqrt()
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<f32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: f32) -> Point {
+        Point {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Size {
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Size {
+    pub fn new(w: f32, h: f32) -> Size {
+        Size { w, h }
+    }
+}
+
+// Distances to move each side of a rect inwards by
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Insets {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Insets {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Insets {
+        Insets {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+    pub fn uniform(v: f32) -> Insets {
+        Insets::new(v, v, v, v)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Rect {
     pub x: f32,
     pub y: f32,
@@ -83,40 +168,146 @@ impl Rect {
         self.bottom_right().y
     }
 
-    pub fn add(&self, other: Rect) -> Rect {
+    // Gets the area covered by both rects, which is empty if they don't overlap
+    pub fn intersect(&self, other: Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+
+        Rect {
+            x,
+            y,
+            w: (self.right().min(other.right()) - x).max(0.0),
+            h: (self.bottom().min(other.bottom()) - y).max(0.0),
+        }
+    }
+
+    pub fn center(&self) -> Point {
+        Point {
+            x: self.x + self.w / 2.0,
+            y: self.y + self.h / 2.0,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        Size {
+            w: self.w,
+            h: self.h,
+        }
+    }
+
+    pub fn from_point_size(point: Point, size: Size) -> Rect {
         Rect {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            ..*self
+            x: point.x,
+            y: point.y,
+            w: size.w,
+            h: size.h,
         }
     }
 
-    pub fn add_pt(&self, other: Point) -> Rect {
+    // Gets the smallest rect which covers both rects
+    pub fn union(&self, other: Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+
         Rect {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            ..*self
+            x,
+            y,
+            w: self.right().max(other.right()) - x,
+            h: self.bottom().max(other.bottom()) - y,
         }
     }
 
-    pub fn sub(&self, other: Rect) -> Rect {
+    pub fn contains_rect(&self, other: Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub fn shrink(&self, insets: Insets) -> Rect {
         Rect {
-            x: self.x - other.x,
-            y: self.y - other.y,
+            x: self.x + insets.left,
+            y: self.y + insets.top,
+            w: (self.w - insets.left - insets.right).max(0.0),
+            h: (self.h - insets.top - insets.bottom).max(0.0),
+        }
+    }
+
+    // The cut_* functions remove a strip of the specified size from one side of the rect and return it
+    // Calling them repeatedly on the same rect lays out controls one after another
+    pub fn cut_left(&mut self, amount: f32) -> Rect {
+        let amount = amount.clamp(0.0, self.w);
+        let cut = Rect { w: amount, ..*self };
+        self.x += amount;
+        self.w -= amount;
+        cut
+    }
+
+    pub fn cut_right(&mut self, amount: f32) -> Rect {
+        let amount = amount.clamp(0.0, self.w);
+        self.w -= amount;
+        Rect {
+            x: self.right(),
+            w: amount,
             ..*self
         }
     }
 
-    pub fn sub_pt(&self, other: Point) -> Rect {
+    pub fn cut_top(&mut self, amount: f32) -> Rect {
+        let amount = amount.clamp(0.0, self.h);
+        let cut = Rect { h: amount, ..*self };
+        self.y += amount;
+        self.h -= amount;
+        cut
+    }
+
+    pub fn cut_bottom(&mut self, amount: f32) -> Rect {
+        let amount = amount.clamp(0.0, self.h);
+        self.h -= amount;
         Rect {
-            x: self.x - other.x,
-            y: self.y - other.y,
+            y: self.bottom(),
+            h: amount,
             ..*self
         }
     }
 
-    pub fn to_sdl(&self) -> SdlRect {
-        SdlRect::new(self.x as i32, self.y as i32, self.w as u32, self.h as u32)
+    // Splits the rect into a left and a right part, with the left one being the specified width
+    pub fn split_x(&self, amount: f32) -> (Rect, Rect) {
+        let mut right = *self;
+        let left = right.cut_left(amount);
+        (left, right)
+    }
+
+    // Splits the rect into a top and a bottom part, with the top one being the specified height
+    pub fn split_y(&self, amount: f32) -> (Rect, Rect) {
+        let mut bottom = *self;
+        let top = bottom.cut_top(amount);
+        (top, bottom)
+    }
+}
+
+// Adding or subtracting a point moves the rect without changing its size
+impl Add<Point> for Rect {
+    type Output = Rect;
+
+    fn add(self, offset: Point) -> Rect {
+        Rect {
+            x: self.x + offset.x,
+            y: self.y + offset.y,
+            ..self
+        }
+    }
+}
+
+impl Sub<Point> for Rect {
+    type Output = Rect;
+
+    fn sub(self, offset: Point) -> Rect {
+        Rect {
+            x: self.x - offset.x,
+            y: self.y - offset.y,
+            ..self
+        }
     }
 }
 
@@ -1,17 +1,173 @@
 use crate::geo::Point;
+use std::time::{Duration, Instant};
 
-#[derive(Clone, Copy, Default)]
+// Where the mouse is reported to be while it's outside the window, so nothing is hovered
+const OUTSIDE_WINDOW: Point = Point { x: -1.0, y: -1.0 };
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Enter,
+    Space,
+    Tab,
+    Backspace,
+    Delete,
+    Escape,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MouseButton {
+    Primary,
+    Secondary,
+    Middle,
+}
+
+impl MouseButton {
+    pub const ALL: [MouseButton; 3] = [
+        MouseButton::Primary,
+        MouseButton::Secondary,
+        MouseButton::Middle,
+    ];
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Input {
     pub mouse_position: Point,
+    // Notches the wheel was turned since the last frame, positive ones away from the user to scroll up
     pub mouse_wheel: i8,
     pub primary_down: bool,
+    pub secondary_down: bool,
+    pub middle_down: bool,
+    // Keys which were pressed since the last frame, in order
+    pub pressed_keys: Vec<Key>,
+    // Text which was typed since the last frame, after keyboard layouts and IMEs were applied
+    pub text: String,
+    pub modifiers: Modifiers,
+    // Time of this input in seconds, relative to any fixed point, which is used to detect double clicks
+    pub time: f64,
 }
 
 impl Input {
+    pub fn button_down(&self, button: MouseButton) -> bool {
+        match button {
+            MouseButton::Primary => self.primary_down,
+            MouseButton::Secondary => self.secondary_down,
+            MouseButton::Middle => self.middle_down,
+        }
+    }
     fn wheel_up(&self) -> bool {
-        self.mouse_wheel < 0
+        self.mouse_wheel > 0
     }
     fn wheel_down(&self) -> bool {
-        self.mouse_wheel > 0
+        self.mouse_wheel < 0
+    }
+}
+
+// Accumulates the events of a windowing library into the Input of the next frame
+// Backends translate their events and leave everything which doesn't depend on the library to this
+pub struct InputAccumulator {
+    input: Input,
+    start_time: Instant,
+}
+
+impl Default for InputAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputAccumulator {
+    pub fn new() -> InputAccumulator {
+        InputAccumulator {
+            input: Input {
+                mouse_position: OUTSIDE_WINDOW,
+                ..Default::default()
+            },
+            start_time: Instant::now(),
+        }
+    }
+
+    pub fn mouse_moved(&mut self, position: Point) {
+        self.input.mouse_position = position;
+    }
+
+    // While a button is held, the mouse keeps being reported so dragging can continue outside the window
+    pub fn mouse_left(&mut self) {
+        if MouseButton::ALL.iter().all(|x| !self.input.button_down(*x)) {
+            self.input.mouse_position = OUTSIDE_WINDOW;
+        }
+    }
+
+    pub fn button_changed(&mut self, button: MouseButton, down: bool) {
+        match button {
+            MouseButton::Primary => self.input.primary_down = down,
+            MouseButton::Secondary => self.input.secondary_down = down,
+            MouseButton::Middle => self.input.middle_down = down,
+        }
+    }
+
+    // Adds whole notches, positive ones away from the user, to the ones turned since the last frame
+    pub fn wheel_turned(&mut self, notches: f64) {
+        self.input.mouse_wheel = self
+            .input
+            .mouse_wheel
+            .saturating_add(notches.clamp(i8::MIN as f64, i8::MAX as f64) as i8);
+    }
+
+    // Called for every press, including the repeated ones while a key is held down
+    pub fn key_pressed(&mut self, key: Key) {
+        self.input.pressed_keys.push(key);
+    }
+
+    // Keys like Enter and Backspace come with control characters, which are reported as keys instead
+    pub fn text_typed(&mut self, text: &str) {
+        self.input
+            .text
+            .extend(text.chars().filter(|x| !x.is_control()));
+    }
+
+    pub fn modifiers_changed(&mut self, modifiers: Modifiers) {
+        self.input.modifiers = modifiers;
+    }
+
+    // Releases happening while we're in the background are never reported, so we let go of everything
+    pub fn focus_lost(&mut self) {
+        self.input.primary_down = false;
+        self.input.secondary_down = false;
+        self.input.middle_down = false;
+        self.input.modifiers = Modifiers::default();
+    }
+
+    // Gets how long it is until the time of the input clock, e.g. to wait for FrameOutput::repaint_at
+    pub fn time_until(&self, time: f64) -> Duration {
+        Duration::from_secs_f64((time - self.start_time.elapsed().as_secs_f64()).max(0.0))
+    }
+
+    // Gets the input for the next call to Ugui::begin, consuming everything which is only reported once
+    pub fn take_input(&mut self) -> Input {
+        self.input.time = self.start_time.elapsed().as_secs_f64();
+        let input = self.input.clone();
+        self.input.mouse_wheel = 0;
+        self.input.pressed_keys.clear();
+        self.input.text.clear();
+        input
     }
 }
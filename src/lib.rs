@@ -1,22 +1,189 @@
+pub mod access;
+#[cfg(feature = "accesskit")]
+pub mod accesskit_adapter;
+pub mod animation;
 pub mod control;
+pub mod debug;
 pub mod geo;
 pub mod input;
+pub mod output;
+pub mod painter;
+#[cfg(feature = "recording")]
+pub mod recording;
+pub mod response;
+#[cfg(feature = "sdl2")]
+pub mod sdl_input;
+#[cfg(feature = "sdl2")]
+pub mod sdl_painter;
+#[cfg(feature = "software")]
+pub mod software_painter;
 pub mod standard_styler;
 pub mod styler;
+pub mod testing;
+#[cfg(feature = "winit")]
+pub mod winit_input;
+#[cfg(feature = "winit")]
+pub mod winit_painter;
 
-use crate::control::{Button, Control, Listbox, Scrollbar, Textbox};
-use crate::geo::{Point, Rect};
-use crate::input::Input;
+use crate::access::{AccessAction, AccessNode, AccessRole};
+use crate::control::{
+    Button, ButtonMode, Control, Label, Listbox, MessageBoxButtons, MessageBoxResult, Orientation,
+    Scrollbar, SortDirection, Splitter, SplitterPane, SplitterPanes, Table, TableColumn, TableHit,
+    TableSort, Textbox, TreeHit, TreeModel, TreeRow, TreeView, Window, WindowStatus,
+};
+use crate::debug::{DebugControl, DebugOverlay};
+use crate::geo::{Point, Rect, Size};
+use crate::input::{Input, Key, MouseButton};
+use crate::output::{CursorIcon, FrameOutput};
+#[cfg(feature = "recording")]
+use crate::recording::{RecordedFrame, RecordedValue, Recording};
+use crate::response::Response;
 use crate::styler::Styler;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // state for all types of controls flattened into one struct
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 struct PersistentControlState {
+    // The control this one is a part of, e.g. the listbox owning a scrollbar
+    parent: Option<i64>,
     scrollbar_value: f32,
     textbox_caret: usize,
     textbox_selection_start: Option<usize>,
     textbox_selection_end: Option<usize>,
+    table_column_width: Option<f32>,
+    table_column_drag_start_width: f32,
+    scroll_area_content_size: Size,
+    window_collapsed: bool,
+    // Where the user moved the divider to, the splitter's own ratio is used until then
+    splitter_ratio: Option<f32>,
+    splitter_collapsed: bool,
+    // Number of tree view rows which aren't hidden inside collapsed nodes, along with the model revision it was counted at
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tree_view_row_count: Option<(u64, usize)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    splitter_drag_start_size: f32,
+    // The scroll value the content is shown at, which trails behind the scrollbars while smooth scrolling
+    #[cfg_attr(feature = "serde", serde(skip))]
+    smooth_scroll: Option<Point>,
+    // The visible part of the window as of its last placement, which windows are hit-tested against
+    #[cfg_attr(feature = "serde", serde(skip))]
+    window_rect: Rect,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    window_drag: Option<WindowDrag>,
+    // Where the message box was moved to, it's centered in the viewport until then
+    #[cfg_attr(feature = "serde", serde(skip))]
+    message_box_rect: Option<Rect>,
+    // Whether the control was focused when its last response was built
+    #[cfg_attr(feature = "serde", serde(skip))]
+    had_focus: bool,
+    // The frame this state was last accessed in by its control
+    #[cfg_attr(feature = "serde", serde(skip))]
+    last_seen_frame: u64,
+}
+
+const SCROLLBAR_SIZE: f32 = 16.0;
+// How far one notch of the mouse wheel scrolls
+const WHEEL_SCROLL_STEP: f32 = 48.0;
+const DEFAULT_DRAG_THRESHOLD: f32 = 4.0;
+const TABLE_MIN_COLUMN_WIDTH: f32 = 16.0;
+const DEFAULT_STATE_RETENTION_FRAMES: u64 = 600;
+// Presses which follow each other within this time and distance count as one multi-click
+const MULTI_CLICK_TIME: f64 = 0.5;
+const MULTI_CLICK_DISTANCE: f32 = 4.0;
+const MAX_FRAME_DELTA: f64 = 1.0 / 30.0;
+const SPLITTER_DIVIDER_SIZE: f32 = 6.0;
+const WINDOW_TITLE_BAR_HEIGHT: f32 = 24.0;
+// Margin around the buttons in the title bar
+const WINDOW_BUTTON_MARGIN: f32 = 4.0;
+// How far from a window's edges pushing down the mouse starts resizing instead of moving it
+const WINDOW_RESIZE_BORDER: f32 = 4.0;
+const WINDOW_MIN_WIDTH: f32 = 96.0;
+const WINDOW_MIN_HEIGHT: f32 = 48.0;
+// Size of the area the modal overlay covers when no viewport size was set, which is larger than any realistic screen
+const MODAL_OVERLAY_FALLBACK_SIZE: f32 = 16384.0;
+const MESSAGE_BOX_WIDTH: f32 = 320.0;
+const MESSAGE_BOX_HEIGHT: f32 = 140.0;
+const MESSAGE_BOX_MARGIN: f32 = 10.0;
+const MESSAGE_BOX_BUTTON_WIDTH: f32 = 80.0;
+const MESSAGE_BOX_BUTTON_HEIGHT: f32 = 23.0;
+
+// How a window is dragged, decided by where the mouse got pushed down
+#[derive(Copy, Clone, Debug, Default)]
+struct WindowDrag {
+    start_rect: Rect,
+    moving: bool,
+    left: bool,
+    top: bool,
+    right: bool,
+    bottom: bool,
+}
+
+impl WindowDrag {
+    // Gets the drag which pushing down the mouse at the point would start
+    fn at(rect: Rect, resizable: bool, point: Point) -> WindowDrag {
+        let point = point - rect.top_left();
+        let mut drag = WindowDrag {
+            start_rect: rect,
+            left: resizable && point.x < WINDOW_RESIZE_BORDER,
+            top: resizable && point.y < WINDOW_RESIZE_BORDER,
+            right: resizable && point.x >= rect.w - WINDOW_RESIZE_BORDER,
+            bottom: resizable && point.y >= rect.h - WINDOW_RESIZE_BORDER,
+            ..Default::default()
+        };
+        drag.moving = !(drag.left || drag.top || drag.right || drag.bottom)
+            && point.y < WINDOW_TITLE_BAR_HEIGHT;
+        drag
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        match (self.left || self.right, self.top || self.bottom) {
+            (true, true) if self.left == self.top => CursorIcon::ResizeNwSe,
+            (true, true) => CursorIcon::ResizeNeSw,
+            (true, false) => CursorIcon::ResizeHorizontal,
+            (false, true) => CursorIcon::ResizeVertical,
+            (false, false) => CursorIcon::Default,
+        }
+    }
+
+    fn apply(&self, offset: Point) -> Rect {
+        let start = self.start_rect;
+        let mut rect = start;
+        if self.moving {
+            rect = rect + offset;
+        }
+        if self.left {
+            let dx = offset.x.min(start.w - WINDOW_MIN_WIDTH);
+            rect.x += dx;
+            rect.w -= dx;
+        }
+        if self.top {
+            let dy = offset.y.min(start.h - WINDOW_MIN_HEIGHT);
+            rect.y += dy;
+            rect.h -= dy;
+        }
+        if self.right {
+            rect.w = (start.w + offset.x).max(WINDOW_MIN_WIDTH);
+        }
+        if self.bottom {
+            rect.h = (start.h + offset.y).max(WINDOW_MIN_HEIGHT);
+        }
+        rect
+    }
+}
+
+// Transitions of one mouse button, computed once per frame in begin
+#[derive(Copy, Clone, Default)]
+struct ButtonState {
+    // The button went down this frame
+    pressed: bool,
+    // The button went up this frame
+    released: bool,
+    down_position: Point,
+    down_time: f64,
+    // How many presses in a row happened close to each other, starting at 1 for a single press
+    click_count: u32,
 }
 
 #[derive(Clone, Default)]
@@ -25,30 +192,324 @@ pub struct PersistentState {
     current_input: Input,
     last_input: Input,
     mouse_down_position: Point,
-    clear_active_control_after_mouse_up: bool,
+    // Indexed by MouseButton
+    buttons: [ButtonState; 3],
+    // Whether the mouse moved further than the drag threshold since it was pushed down
+    dragging: bool,
+    // The control receiving keyboard input, which is independent of the mouse-driven active control
+    focused_control: Option<i64>,
     control_state: HashMap<i64, PersistentControlState>,
+    // Expanded nodes of all tree views, keyed by the tree view's uid and the node
+    expanded_tree_nodes: HashSet<(i64, u64)>,
+    // Number of frames which were ended so far
+    frame: u64,
+    // Seconds since the last frame, which animations advance by
+    frame_delta: f32,
+    // Uids of all windows from back to front
+    window_order: Vec<i64>,
+    // The layer whose controls the mouse is over, none while those are blocked by a modal
+    hovered_layer: Option<usize>,
 }
 
 impl PersistentState {
     pub fn new() -> PersistentState {
-        return PersistentState {
-            clear_active_control_after_mouse_up: true,
-            ..Default::default()
-        };
+        Default::default()
     }
 }
 
+// Bumped whenever the meaning of saved state changes, so stale files are rejected instead of misinterpreted
+#[cfg(feature = "serde")]
+const SAVED_STATE_VERSION: u32 = 1;
+
+// The durable parts of PersistentState, which are what save_state writes
+// Everything is kept sorted, so saving the same state always produces the same output
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedState {
+    version: u32,
+    control_state: std::collections::BTreeMap<i64, PersistentControlState>,
+    expanded_tree_nodes: std::collections::BTreeSet<(i64, u64)>,
+}
+
+// A scroll area or window which is currently being filled with controls
+struct ScrollArea {
+    uid: i64,
+    // Screen position of the content's top-left corner, which children are placed relative to
+    origin: Point,
+    // Extents of all controls placed inside the scroll area so far
+    content_size: Size,
+}
+
 pub struct Ugui<T: Styler> {
     pub styler: T,
     pub persistent_state: PersistentState,
+    scroll_areas: Vec<ScrollArea>,
+    // Screen space clip rects, each one already intersected with the ones below it
+    clip_stack: Vec<Rect>,
+    scale: f32,
+    // Uids of all focusable controls in the order they were placed this frame, which is the tab order
+    focus_order: Vec<i64>,
+    // Whether any control was pushed by the mouse press this frame
+    click_claimed: bool,
+    // The innermost scrollable control under the mouse as of the last frame, which the mouse wheel scrolls
+    // Scroll areas are placed before the controls inside them, so it's only known once the frame ended
+    wheel_target: Option<i64>,
+    hovered_scrollable: Option<i64>,
+    drag_threshold: f32,
+    drag_started: bool,
+    // What the controls placed so far this frame requested from the host
+    output: FrameOutput,
+    // How many frames control state survives without its control being placed
+    state_retention_frames: u64,
+    // Uids whose state is never evicted
+    pinned_controls: HashSet<i64>,
+    smooth_scrolling: bool,
+    #[cfg(feature = "recording")]
+    recording: Option<Recording>,
+    debug_overlay: bool,
+    // Controls which built a response this frame, only collected while the debug overlay is shown
+    debug_controls: Vec<DebugControl>,
+    accessibility: bool,
+    // Nodes of all controls placed this frame, only collected while accessibility is enabled
+    access_nodes: Vec<AccessNode>,
+    // Actions queued for the next frame, and the ones of this frame which weren't consumed by their control yet
+    queued_access_actions: Vec<(i64, AccessAction)>,
+    access_actions: Vec<(i64, AccessAction)>,
+    // Size of the area windows are kept inside of, in physical pixels
+    viewport_size: Option<Size>,
+    // Uids of the windows which are currently being filled with controls, and of all windows placed this frame
+    window_stack: Vec<i64>,
+    windows_this_frame: Vec<i64>,
+    // The frontmost windows under the mouse and under the position it was last pushed down at
+    hovered_window: Option<i64>,
+    pressed_window: Option<i64>,
+    // The modal placed last frame, which blocks input to everything outside of it, and the one placed this frame
+    modal: Option<i64>,
+    modal_this_frame: Option<i64>,
 }
 
 impl<T: Styler> Ugui<T> {
+    pub fn new(styler: T) -> Ugui<T> {
+        Ugui {
+            styler,
+            persistent_state: PersistentState::new(),
+            scroll_areas: vec![],
+            clip_stack: vec![],
+            scale: 1.0,
+            focus_order: vec![],
+            click_claimed: false,
+            wheel_target: None,
+            hovered_scrollable: None,
+            drag_threshold: DEFAULT_DRAG_THRESHOLD,
+            drag_started: false,
+            output: FrameOutput::default(),
+            state_retention_frames: DEFAULT_STATE_RETENTION_FRAMES,
+            pinned_controls: HashSet::new(),
+            smooth_scrolling: true,
+            #[cfg(feature = "recording")]
+            recording: None,
+            debug_overlay: false,
+            debug_controls: vec![],
+            accessibility: false,
+            access_nodes: vec![],
+            queued_access_actions: vec![],
+            access_actions: vec![],
+            viewport_size: None,
+            window_stack: vec![],
+            windows_this_frame: vec![],
+            hovered_window: None,
+            pressed_window: None,
+            modal: None,
+            modal_this_frame: None,
+        }
+    }
+
+    // Sets how far the mouse has to move while pushed down before it counts as dragging
+    pub fn set_drag_threshold(&mut self, drag_threshold: f32) {
+        self.drag_threshold = drag_threshold;
+    }
+
+    // Sets whether scrollable controls glide to new scroll values instead of jumping there, which is on by default
+    pub fn set_smooth_scrolling(&mut self, smooth_scrolling: bool) {
+        self.smooth_scrolling = smooth_scrolling;
+    }
+
+    // Sets the factor between logical units, which controls are laid out in, and physical pixels
+    // The input passed to begin is expected to be in physical pixels
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        self.output.repaint = true;
+        self.styler.set_scale(scale);
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    // Sets the size of the area windows are kept inside of, in physical pixels like the input
+    pub fn set_viewport_size(&mut self, size: Size) {
+        self.viewport_size = Some(size);
+        self.output.repaint = true;
+    }
+
+    // Sets how many frames a control can go without being placed before its state is discarded
+    pub fn set_state_retention_frames(&mut self, frames: u64) {
+        self.state_retention_frames = frames;
+    }
+
+    // Keeps the control's state around even while it isn't placed, e.g. for the contents of hidden tabs
+    // The state of its sub-controls, like the scrollbars of a listbox, is kept along with it
+    pub fn pin_state(&mut self, uid: i64) {
+        self.pinned_controls.insert(uid);
+    }
+
+    pub fn unpin_state(&mut self, uid: i64) {
+        self.pinned_controls.remove(&uid);
+    }
+
+    // Starts recording the input and the values of all controls for each frame, discarding any previous recording
+    // Only the input is kept, so recordings can only be replayed if they were started before the first frame
+    #[cfg(feature = "recording")]
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::default());
+    }
+
+    #[cfg(feature = "recording")]
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    #[cfg(feature = "recording")]
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    // Shows the rects, uids and state of all controls on top of them, which helps with debugging layouts
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.debug_overlay = enabled;
+        self.output.repaint = true;
+    }
+
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay
+    }
+
+    // Builds an accessibility tree from the controls placed each frame, which is available via access_nodes
+    pub fn set_accessibility(&mut self, enabled: bool) {
+        self.accessibility = enabled;
+    }
+
+    pub fn accessibility(&self) -> bool {
+        self.accessibility
+    }
+
+    // Gets the nodes of all controls in the order they were placed, which is complete once the frame ended
+    pub fn access_nodes(&self) -> &[AccessNode] {
+        &self.access_nodes
+    }
+
+    // Applies an action from assistive technology to the control with the uid during the next frame
+    pub fn queue_access_action(&mut self, uid: i64, action: AccessAction) {
+        self.queued_access_actions.push((uid, action));
+        self.output.repaint = true;
+    }
+
+    // Makes the output of the current frame ask for another one, e.g. after changing something shown by a control
+    pub fn request_repaint(&mut self) {
+        self.output.repaint = true;
+    }
+
+    // Makes the output of the current frame ask for another one once the seconds passed, e.g. for timers
+    pub fn request_repaint_after(&mut self, delay: f64) {
+        self.request_repaint_at(self.persistent_state.current_input.time + delay);
+    }
+
+    fn request_repaint_at(&mut self, time: f64) {
+        if time <= self.persistent_state.current_input.time {
+            self.output.repaint = true;
+            return;
+        }
+        self.output.repaint_at = Some(self.output.repaint_at.map_or(time, |x| x.min(time)));
+    }
+
+    // Whether running a frame with the input would change anything on screen
+    // Hosts can skip the frame otherwise and wait for more events, or until the time the last output asked for
+    pub fn needs_frame(&self, input: &Input) -> bool {
+        // The last input was already scaled to logical units by begin, while this one is still physical
+        let last_input = &self.persistent_state.current_input;
+        self.persistent_state.frame == 0
+            || self.output.repaint
+            || self.output.repaint_at.is_some_and(|x| input.time >= x)
+            || !input.pressed_keys.is_empty()
+            || !input.text.is_empty()
+            || input.mouse_wheel != 0
+            || input.mouse_position * (1.0 / self.scale) != last_input.mouse_position
+            || input.primary_down != last_input.primary_down
+            || input.secondary_down != last_input.secondary_down
+            || input.middle_down != last_input.middle_down
+            || input.modifiers != last_input.modifiers
+    }
+
+    // Writes scroll positions, column widths, expanded tree nodes and the like
+    // Transient state like the input or the active control isn't saved
+    #[cfg(feature = "serde")]
+    pub fn save_state(&self, writer: impl std::io::Write) -> Result<(), String> {
+        let saved_state = SavedState {
+            version: SAVED_STATE_VERSION,
+            control_state: self
+                .persistent_state
+                .control_state
+                .iter()
+                .map(|(uid, state)| (*uid, *state))
+                .collect(),
+            expanded_tree_nodes: self
+                .persistent_state
+                .expanded_tree_nodes
+                .iter()
+                .copied()
+                .collect(),
+        };
+        serde_json::to_writer(writer, &saved_state).map_err(|e| e.to_string())
+    }
+
+    // Replaces the durable state with what was written by save_state
+    #[cfg(feature = "serde")]
+    pub fn load_state(&mut self, reader: impl std::io::Read) -> Result<(), String> {
+        let saved_state: SavedState = serde_json::from_reader(reader).map_err(|e| e.to_string())?;
+        if saved_state.version != SAVED_STATE_VERSION {
+            return Err(format!(
+                "Saved state has version {}, but only version {} is supported",
+                saved_state.version, SAVED_STATE_VERSION
+            ));
+        }
+
+        // Loaded state counts as seen now, otherwise it would be evicted before its controls get placed
+        let frame = self.persistent_state.frame;
+        self.persistent_state.control_state = saved_state
+            .control_state
+            .into_iter()
+            .map(|(uid, state)| {
+                (
+                    uid,
+                    PersistentControlState {
+                        last_seen_frame: frame,
+                        ..state
+                    },
+                )
+            })
+            .collect();
+        self.persistent_state.expanded_tree_nodes =
+            saved_state.expanded_tree_nodes.into_iter().collect();
+        Ok(())
+    }
+
     fn ensure_control_data_exists(&mut self, uid: i64) {
+        let frame = self.persistent_state.frame;
         self.persistent_state
             .control_state
             .entry(uid)
-            .or_insert_with(Default::default);
+            .or_insert_with(Default::default)
+            .last_seen_frame = frame;
     }
     fn get_control_data(&mut self, uid: i64) -> PersistentControlState {
         self.ensure_control_data_exists(uid);
@@ -66,31 +527,351 @@ impl<T: Styler> Ugui<T> {
         self.persistent_state.control_state.insert(uid, new_data);
     }
 
+    // Gets the uid of a control which is part of the parent, which owns the uids following its own
+    fn sub_control_uid(&mut self, parent: i64, offset: i64) -> i64 {
+        let uid = parent + offset;
+        self.update_control_data(uid, |x| PersistentControlState {
+            parent: Some(parent),
+            ..x
+        });
+        uid
+    }
+
+    // Moves a control into screen space and grows the extents of the scroll area it's placed in
+    fn place(&mut self, mut control: Control) -> Control {
+        if let Some(scroll_area) = self.scroll_areas.last_mut() {
+            scroll_area.content_size.w = scroll_area.content_size.w.max(control.rect.right());
+            scroll_area.content_size.h = scroll_area.content_size.h.max(control.rect.bottom());
+            control.rect = control.rect + scroll_area.origin;
+        }
+        control
+    }
+
     fn process_push(&mut self, control: Control) -> bool {
         if !control.enabled {
             return false;
         }
 
-        if self.persistent_state.current_input.primary_down
-            && !self.persistent_state.last_input.primary_down
+        // Parts of controls which are clipped away, e.g. by being scrolled out of view, can't be interacted with
+        if self
+            .clip_stack
+            .last()
+            .is_some_and(|x| !self.persistent_state.mouse_down_position.inside(*x))
+        {
+            return false;
+        }
+
+        // Windows cover everything below them, so only controls in the frontmost window under the mouse can be pushed
+        if self.window_stack.last().copied() != self.pressed_window || self.blocked_by_modal() {
+            return false;
+        }
+
+        if self.mouse_pressed(MouseButton::Primary)
             && self
                 .persistent_state
                 .mouse_down_position
                 .inside(control.rect)
         {
             self.persistent_state.active_control = Some(control.uid);
-            self.persistent_state.clear_active_control_after_mouse_up = true;
+            self.click_claimed = true;
             return true;
         }
 
         false
     }
-    pub fn button(&mut self, control: Control, button: Button) -> bool {
+    fn is_focused(&self, uid: i64) -> bool {
+        self.persistent_state
+            .focused_control
+            .is_some_and(|x| x == uid)
+    }
+
+    // Gets how far the mouse moved since being pushed down, which stays zero until the drag threshold is exceeded
+    fn drag_offset(&self) -> Point {
+        if !self.persistent_state.dragging {
+            return Point::default();
+        }
+        self.persistent_state.current_input.mouse_position
+            - self.persistent_state.mouse_down_position
+    }
+
+    fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.persistent_state.buttons[button as usize].pressed
+    }
+
+    fn mouse_released(&self, button: MouseButton) -> bool {
+        self.persistent_state.buttons[button as usize].released
+    }
+
+    fn click_count(&self, button: MouseButton) -> u32 {
+        self.persistent_state.buttons[button as usize].click_count
+    }
+
+    fn key_pressed(&self, key: Key) -> bool {
+        self.persistent_state
+            .current_input
+            .pressed_keys
+            .contains(&key)
+    }
+
+    // Adds the control to the tab order and focuses it if it was pushed
+    fn process_focus(&mut self, control: Control, pushed: bool) {
+        if !control.enabled || self.blocked_by_modal() {
+            return;
+        }
+
+        self.focus_order.push(control.uid);
+        if pushed {
+            self.persistent_state.focused_control = Some(control.uid);
+        }
+    }
+
+    fn response<V: std::fmt::Debug>(
+        &mut self,
+        control: Control,
+        rect: Rect,
+        pushed: bool,
+        value: V,
+        changed: bool,
+    ) -> Response<V> {
+        #[cfg(feature = "recording")]
+        if let Some(frame) = self.recording.as_mut().and_then(|x| x.frames.last_mut()) {
+            frame.values.push(RecordedValue {
+                uid: control.uid,
+                value: format!("{:?}", value),
+            });
+        }
+
+        let focused = self.is_focused(control.uid);
+        let had_focus = self.get_control_data(control.uid).had_focus;
+        self.update_control_data(control.uid, |x| PersistentControlState {
+            had_focus: focused,
+            ..x
+        });
+
+        let visible_rect = match self.clip_stack.last() {
+            Some(clip) => rect.intersect(*clip),
+            None => rect,
+        };
+        if self.debug_overlay {
+            self.push_debug_control(control, visible_rect);
+        }
+        let released = self.mouse_released(MouseButton::Primary);
+        let click_count = self.click_count(MouseButton::Primary);
+        let secondary_down_inside = self.persistent_state.buttons[MouseButton::Secondary as usize]
+            .down_position
+            .inside(visible_rect);
+        let secondary_released = self.mouse_released(MouseButton::Secondary);
+        let input = &self.persistent_state.current_input;
+        let last_input = &self.persistent_state.last_input;
+        let inside = input.mouse_position.inside(visible_rect)
+            && self.window_stack.last().copied() == self.hovered_window
+            && !self.blocked_by_modal();
+        let active = self
+            .persistent_state
+            .active_control
+            .is_some_and(|x| x == control.uid);
+
+        let response = Response {
+            value,
+            hovered: control.enabled
+                && inside
+                && (active || self.persistent_state.active_control.is_none()),
+            pressed: pushed,
+            clicked: active && inside && released,
+            double_clicked: pushed && click_count == 2,
+            triple_clicked: pushed && click_count == 3,
+            secondary_clicked: control.enabled
+                && inside
+                && secondary_released
+                && secondary_down_inside,
+            changed,
+            drag_delta: if !active || !self.persistent_state.dragging {
+                Point::default()
+            } else if self.drag_started {
+                // Movement within the threshold was held back until now, so it's reported all at once
+                input.mouse_position - self.persistent_state.mouse_down_position
+            } else {
+                input.mouse_position - last_input.mouse_position
+            },
+            gained_focus: focused && !had_focus,
+            lost_focus: !focused && had_focus,
+            rect,
+        };
+
+        // Whatever the caller changes in response to this is only shown next frame
+        if response.pressed
+            || response.clicked
+            || response.secondary_clicked
+            || response.changed
+            || response.gained_focus
+            || response.lost_focus
+        {
+            self.output.repaint = true;
+        }
+        response
+    }
+
+    fn push_debug_control(&mut self, control: Control, visible_rect: Rect) {
+        let mut collides = false;
+        for other in self.debug_controls.iter_mut() {
+            if other.uid == control.uid {
+                other.collides = true;
+                collides = true;
+            }
+        }
+        self.debug_controls.push(DebugControl {
+            uid: control.uid,
+            rect: visible_rect,
+            enabled: control.enabled,
+            collides,
+        });
+    }
+
+    fn push_access_node(
+        &mut self,
+        control: Control,
+        rect: Rect,
+        role: AccessRole,
+        name: &str,
+        value: Option<String>,
+        numeric_value: Option<f32>,
+    ) {
+        if !self.accessibility {
+            return;
+        }
+        let bounds = match self.clip_stack.last() {
+            Some(clip) => rect.intersect(*clip),
+            None => rect,
+        };
+        self.access_nodes.push(AccessNode {
+            uid: control.uid,
+            role,
+            name: name.to_string(),
+            value,
+            numeric_value,
+            enabled: control.enabled,
+            focused: self.is_focused(control.uid),
+            bounds,
+            // Windows are on the stack while their own node is pushed, but they can't be nested
+            window: self
+                .window_stack
+                .last()
+                .copied()
+                .filter(|x| *x != control.uid),
+        });
+    }
+
+    // Whether a modal is open and the control being placed isn't part of it
+    fn blocked_by_modal(&self) -> bool {
+        self.modal
+            .is_some_and(|x| self.window_stack.last() != Some(&x))
+    }
+
+    // Takes the first action queued for the control, which disabled controls don't receive
+    fn take_access_action(&mut self, control: Control) -> Option<AccessAction> {
+        if !control.enabled || self.blocked_by_modal() {
+            return None;
+        }
+        let index = self
+            .access_actions
+            .iter()
+            .position(|x| x.0 == control.uid)?;
+        Some(self.access_actions.remove(index).1)
+    }
+
+    fn draw_debug_overlay(&mut self) {
+        let mouse_position = self.persistent_state.current_input.mouse_position;
+        // Controls placed later are drawn on top, so they're the ones under the mouse
+        let hovered_control = self
+            .debug_controls
+            .iter()
+            .rev()
+            .find(|x| mouse_position.inside(x.rect))
+            .map(|x| x.uid);
+
+        let overlay = DebugOverlay {
+            controls: std::mem::take(&mut self.debug_controls),
+            active_control: self.persistent_state.active_control,
+            hovered_control,
+            focused_control: self.persistent_state.focused_control,
+            hovered_state: hovered_control.and_then(|x| {
+                self.persistent_state
+                    .control_state
+                    .get(&x)
+                    .map(|x| format!("{:#?}", x))
+            }),
+            draw_stats: self.styler.draw_stats(),
+        };
+        self.styler.set_clip(None);
+        self.styler
+            .set_layer(self.persistent_state.window_order.len() + 1);
+        self.styler.debug_overlay(&overlay);
+    }
+
+    fn draw_focus_ring(&mut self, control: Control) {
+        if self.is_focused(control.uid) {
+            self.styler.focus_ring(control);
+        }
+    }
+
+    pub fn label(&mut self, control: Control, label: Label) -> Response {
+        let control = self.place(control);
+        self.push_access_node(
+            control,
+            control.rect,
+            AccessRole::Label,
+            "",
+            Some(label.text.to_string()),
+            None,
+        );
+        self.styler.label(control, label);
+        self.response(control, control.rect, false, (), false)
+    }
+
+    pub fn button(&mut self, control: Control, button: Button) -> Response {
+        let control = self.place(control);
         let pushed = self.process_push(control);
+        self.process_focus(control, pushed);
+
+        let mut response = self.response(control, control.rect, pushed, (), false);
+
+        if button.mode == ButtonMode::Press {
+            response.clicked = pushed;
+        }
+
+        // Pressing a focused button via keyboard is a click of its own
+        if control.enabled
+            && self.is_focused(control.uid)
+            && !self.blocked_by_modal()
+            && (self.key_pressed(Key::Enter) || self.key_pressed(Key::Space))
+        {
+            response.pressed = true;
+            response.clicked = true;
+        }
+
+        if self.take_access_action(control) == Some(AccessAction::Press) {
+            response.pressed = true;
+            response.clicked = true;
+        }
+        self.push_access_node(
+            control,
+            control.rect,
+            AccessRole::Button,
+            button.text,
+            None,
+            None,
+        );
+
         self.styler.button(control, button);
-        pushed
+        self.draw_focus_ring(control);
+        response
+    }
+    pub fn scrollbar(&mut self, control: Control, scrollbar: Scrollbar) -> Response<f32> {
+        let control = self.place(control);
+        self.scrollbar_placed(control, scrollbar)
     }
-    pub fn scrollbar(&mut self, control: Control, scrollbar: Scrollbar) -> f32 {
+
+    fn scrollbar_placed(&mut self, control: Control, scrollbar: Scrollbar) -> Response<f32> {
         let pushed = self.process_push(control);
         let is_horizontal = control.rect.w > control.rect.h;
         let mut value = scrollbar.value;
@@ -101,15 +882,10 @@ impl<T: Styler> Ugui<T> {
                 .active_control
                 .is_some_and(|x| x == control.uid)
         {
-            let relative_mouse = self
-                .persistent_state
-                .current_input
-                .mouse_position
-                .sub(control.rect.top_left());
-            let relative_mouse_down = self
-                .persistent_state
-                .mouse_down_position
-                .sub(control.rect.top_left());
+            let relative_mouse =
+                self.persistent_state.current_input.mouse_position - control.rect.top_left();
+            let relative_mouse_down =
+                self.persistent_state.mouse_down_position - control.rect.top_left();
 
             let current: f32;
             let start: f32;
@@ -125,59 +901,116 @@ impl<T: Styler> Ugui<T> {
             value = start + (current - start);
         }
 
+        if let Some(AccessAction::SetValue(new_value)) = self.take_access_action(control) {
+            value = new_value.parse().unwrap_or(value);
+        }
+
         value = value.clamp(0.0, 1.0);
         self.update_control_data(control.uid, |x| PersistentControlState {
             scrollbar_value: value,
             ..x
         });
 
+        self.push_access_node(
+            control,
+            control.rect,
+            AccessRole::Scrollbar,
+            "",
+            None,
+            Some(value),
+        );
         self.styler.scrollbar(control, scrollbar);
 
-        value
+        self.response(
+            control,
+            control.rect,
+            pushed,
+            value,
+            value != scrollbar.value,
+        )
     }
-    pub fn listbox(&mut self, mut control: Control, listbox: Listbox) -> Option<usize> {
+    // Places scrollbars along the control's edges if the content overflows it
+    // Returns the control shrunk down to the remaining viewport and the scroll value
+    fn scrollable(&mut self, mut control: Control, content_size: Size) -> (Control, Point) {
         let horizontal_scrollbar = Control {
-            uid: control.uid + 1,
+            uid: self.sub_control_uid(control.uid, 1),
             enabled: control.enabled,
             rect: Rect {
                 x: control.rect.x,
-                y: control.rect.bottom() - 16.0,
-                w: control.rect.w - 16.0,
-                h: 16.0,
+                y: control.rect.bottom() - SCROLLBAR_SIZE,
+                w: control.rect.w - SCROLLBAR_SIZE,
+                h: SCROLLBAR_SIZE,
             },
         };
 
         let vertical_scrollbar = Control {
-            uid: control.uid + 2,
+            uid: self.sub_control_uid(control.uid, 2),
             enabled: control.enabled,
             rect: Rect {
-                x: control.rect.right() - 16.0,
+                x: control.rect.right() - SCROLLBAR_SIZE,
                 y: control.rect.y,
-                w: 16.0,
+                w: SCROLLBAR_SIZE,
                 h: control.rect.h,
             },
         };
 
-        let horizontal_scrollbar_value = self
+        let mut horizontal_scrollbar_value = self
             .get_control_data(horizontal_scrollbar.uid)
             .scrollbar_value;
-        let vertical_scrollbar_value = self
+        let mut vertical_scrollbar_value = self
             .get_control_data(vertical_scrollbar.uid)
             .scrollbar_value;
 
-        let content_size = self.styler.listbox_get_content_size(control, listbox);
-
         let content_ratio = Point {
-            x: content_size.x / control.rect.w,
-            y: content_size.y / control.rect.h,
+            x: content_size.w / control.rect.w,
+            y: content_size.h / control.rect.h,
         };
+        let overflows_x = content_ratio.x > 1.0;
+        let overflows_y = content_ratio.y > 1.0;
+
+        let visible_rect = match self.clip_stack.last() {
+            Some(clip) => control.rect.intersect(*clip),
+            None => control.rect,
+        };
+        if control.enabled
+            && (overflows_x || overflows_y)
+            && self
+                .persistent_state
+                .current_input
+                .mouse_position
+                .inside(visible_rect)
+            && self.window_stack.last().copied() == self.hovered_window
+            && !self.blocked_by_modal()
+        {
+            self.hovered_scrollable = Some(control.uid);
+        }
+
+        // The wheel scrolls vertically, unless there's only horizontal overflow
+        let wheel = self.persistent_state.current_input.mouse_wheel;
+        if wheel != 0 && self.wheel_target == Some(control.uid) {
+            // Positive values scroll up, like the wheel reports them
+            let distance = -(wheel as f32) * WHEEL_SCROLL_STEP;
+            let viewport = Size {
+                w: control.rect.w - if overflows_y { SCROLLBAR_SIZE } else { 0.0 },
+                h: control.rect.h - if overflows_x { SCROLLBAR_SIZE } else { 0.0 },
+            };
+            if overflows_y {
+                vertical_scrollbar_value = (vertical_scrollbar_value
+                    + distance / (content_size.h - viewport.h))
+                    .clamp(0.0, 1.0);
+            } else {
+                horizontal_scrollbar_value = (horizontal_scrollbar_value
+                    + distance / (content_size.w - viewport.w))
+                    .clamp(0.0, 1.0);
+            }
+        }
 
         let mut scroll = Point::default();
 
         // For horizontal overflow, shrink control bounds and place a horizontal scrollbar
-        if content_ratio.x > 1.0 {
-            control.rect.h -= 16.0;
-            self.scrollbar(
+        if overflows_x {
+            control.rect.h -= SCROLLBAR_SIZE;
+            self.scrollbar_placed(
                 horizontal_scrollbar,
                 Scrollbar {
                     value: horizontal_scrollbar_value,
@@ -185,13 +1018,15 @@ impl<T: Styler> Ugui<T> {
                 },
             );
 
-            scroll.x = horizontal_scrollbar_value;
+            scroll.x = self
+                .get_control_data(horizontal_scrollbar.uid)
+                .scrollbar_value;
         }
 
         // For vertical overflow, shrink control bounds and place a vertical scrollbar
-        if content_ratio.y > 1.0 {
-            control.rect.w -= 16.0;
-            self.scrollbar(
+        if overflows_y {
+            control.rect.w -= SCROLLBAR_SIZE;
+            self.scrollbar_placed(
                 vertical_scrollbar,
                 Scrollbar {
                     value: vertical_scrollbar_value,
@@ -199,9 +1034,37 @@ impl<T: Styler> Ugui<T> {
                 },
             );
 
-            scroll.y = vertical_scrollbar_value;
+            scroll.y = self
+                .get_control_data(vertical_scrollbar.uid)
+                .scrollbar_value;
+        }
+
+        // The content is placed, drawn and hit-tested at the same smoothed value, no matter the styler
+        if self.smooth_scrolling {
+            let target = scroll;
+            let current = self
+                .get_control_data(control.uid)
+                .smooth_scroll
+                .unwrap_or(target);
+            scroll = animation::smooth_scroll(current, target, self.persistent_state.frame_delta);
+            self.update_control_data(control.uid, |x| PersistentControlState {
+                smooth_scroll: Some(scroll),
+                ..x
+            });
+            if scroll != target {
+                self.request_repaint_at(self.persistent_state.current_input.time);
+            }
         }
 
+        (control, scroll)
+    }
+
+    pub fn listbox(&mut self, control: Control, listbox: Listbox) -> Response<Option<usize>> {
+        let control = self.place(control);
+        let rect = control.rect;
+        let content_size = self.styler.listbox_get_content_size(control, listbox);
+        let (control, scroll) = self.scrollable(control, content_size);
+
         let pushed = self.process_push(control);
         let mut index = listbox.index;
 
@@ -215,156 +1078,1315 @@ impl<T: Styler> Ugui<T> {
                 control,
                 listbox,
                 scroll,
-                self.persistent_state
-                    .current_input
-                    .mouse_position
-                    .sub(control.rect.top_left()),
+                self.persistent_state.current_input.mouse_position - control.rect.top_left(),
             );
         }
 
+        if let Some(AccessAction::SetValue(item)) = self.take_access_action(control) {
+            index = listbox.items.iter().position(|x| *x == item).or(index);
+        }
+        self.push_access_node(
+            control,
+            rect,
+            AccessRole::Listbox,
+            "",
+            index
+                .and_then(|x| listbox.items.get(x))
+                .map(|x| x.to_string()),
+            None,
+        );
+
+        self.push_screen_clip(control.rect);
         self.styler.listbox(control, listbox, scroll);
+        self.pop_clip();
 
-        index
+        self.response(control, rect, pushed, index, index != listbox.index)
     }
 
-    pub fn textbox(&mut self, mut control: Control, textbox: Textbox) -> String {
-        let horizontal_scrollbar = Control {
-            uid: control.uid + 1,
-            enabled: control.enabled,
-            rect: Rect {
-                x: control.rect.x,
-                y: control.rect.bottom() - 16.0,
-                w: control.rect.w - 16.0,
-                h: 16.0,
-            },
-        };
-
-        let vertical_scrollbar = Control {
-            uid: control.uid + 2,
-            enabled: control.enabled,
-            rect: Rect {
-                x: control.rect.right() - 16.0,
-                y: control.rect.y,
-                w: 16.0,
-                h: control.rect.h,
-            },
-        };
-
-        let horizontal_scrollbar_value = self
-            .get_control_data(horizontal_scrollbar.uid)
-            .scrollbar_value;
-        let vertical_scrollbar_value = self
-            .get_control_data(vertical_scrollbar.uid)
-            .scrollbar_value;
-
+    pub fn textbox(&mut self, control: Control, textbox: Textbox) -> Response<String> {
+        let control = self.place(control);
+        let rect = control.rect;
         let content_size = self.styler.textbox_get_content_size(control, textbox);
+        let (control, scroll) = self.scrollable(control, content_size);
 
-        let content_ratio = Point {
-            x: content_size.x / control.rect.w,
-            y: content_size.y / control.rect.h,
-        };
-
-        let mut scroll = Point::default();
-
-        // For horizontal overflow, shrink control bounds and place a horizontal scrollbar
-        if content_ratio.x > 1.0 {
-            control.rect.h -= 16.0;
-            self.scrollbar(
-                horizontal_scrollbar,
-                Scrollbar {
-                    value: horizontal_scrollbar_value,
-                    ratio: content_ratio.x,
-                },
-            );
-
-            scroll.x = horizontal_scrollbar_value;
-        }
+        let pushed = self.process_push(control);
+        self.process_focus(control, pushed);
 
-        // For vertical overflow, shrink control bounds and place a vertical scrollbar
-        if content_ratio.y > 1.0 {
-            control.rect.w -= 16.0;
-            self.scrollbar(
-                vertical_scrollbar,
-                Scrollbar {
-                    value: vertical_scrollbar_value,
-                    ratio: content_ratio.y,
-                },
+        if (pushed
+            || self
+                .persistent_state
+                .active_control
+                .is_some_and(|x| x == control.uid))
+            && self.persistent_state.current_input.primary_down
+        {
+            let index = self.styler.textbox_index_at_point(
+                control,
+                textbox,
+                scroll,
+                self.persistent_state.current_input.mouse_position,
             );
+            self.update_control_data(control.uid, |x| PersistentControlState {
+                textbox_caret: index.unwrap(),
+                ..x
+            });
+        }
 
-            scroll.y = vertical_scrollbar_value;
+        let mut text = textbox.text.clone();
+        if let Some(AccessAction::SetValue(new_text)) = self.take_access_action(control) {
+            let caret = new_text.len();
+            text = new_text;
+            self.update_control_data(control.uid, |x| PersistentControlState {
+                textbox_caret: caret,
+                ..x
+            });
         }
+        let mut drawn_caret = None;
+        if control.enabled && self.is_focused(control.uid) && !self.blocked_by_modal() {
+            self.output.text_input = true;
 
-        let pushed = self.process_push(control);
-        let mut text = textbox.text;
+            // The caret is a byte offset into the text, which the caller might have replaced since it was stored
+            let mut caret = self
+                .get_control_data(control.uid)
+                .textbox_caret
+                .min(text.len());
+            while !text.is_char_boundary(caret) {
+                caret -= 1;
+            }
+            let input = &self.persistent_state.current_input;
+            for key in &input.pressed_keys {
+                match key {
+                    Key::Left => {
+                        caret = text[..caret].char_indices().next_back().map_or(0, |x| x.0);
+                    }
+                    Key::Right => {
+                        caret += text[caret..].chars().next().map_or(0, char::len_utf8);
+                    }
+                    Key::Backspace => {
+                        if let Some(c) = text[..caret].chars().next_back() {
+                            caret -= c.len_utf8();
+                            text.remove(caret);
+                        }
+                    }
+                    Key::Delete if caret < text.len() => {
+                        text.remove(caret);
+                    }
+                    Key::Enter => {
+                        text.insert(caret, '\n');
+                        caret += 1;
+                    }
+                    _ => {}
+                }
+            }
+            text.insert_str(caret, &input.text);
+            caret += input.text.len();
 
-        // Since this is a textbox, we shouldn't clear the active control after releasing the mouse
-        // The active control will be cleared manually in the next step
-        if pushed {
-            self.persistent_state.clear_active_control_after_mouse_up = false;
+            self.update_control_data(control.uid, |x| PersistentControlState {
+                textbox_caret: caret,
+                ..x
+            });
+            drawn_caret = Some(caret);
+
+            let caret_rect =
+                self.styler
+                    .textbox_caret_rect(control, Textbox { text: &text }, scroll, caret);
+            self.output.ime_rect = Some(Rect::new(
+                caret_rect.x * self.scale,
+                caret_rect.y * self.scale,
+                caret_rect.w * self.scale,
+                caret_rect.h * self.scale,
+            ));
         }
 
-        if self
-            .persistent_state
-            .active_control
-            .is_some_and(|x| x == control.uid)
-            && !self
+        self.push_access_node(
+            control,
+            rect,
+            AccessRole::Textbox,
+            "",
+            Some(text.clone()),
+            None,
+        );
+
+        // The caret and selection are passed as they are after this frame's input, rather than as of begin
+        let data = self.get_control_data(control.uid);
+        let selection = data
+            .textbox_selection_start
+            .zip(data.textbox_selection_end)
+            .filter(|_| drawn_caret.is_some());
+        self.push_screen_clip(control.rect);
+        self.styler.textbox(
+            control,
+            Textbox { text: &text },
+            scroll,
+            drawn_caret,
+            selection,
+        );
+        self.pop_clip();
+
+        let changed = text != *textbox.text;
+        let response = self.response(control, rect, pushed, text, changed);
+        // The scrollbars keep the default cursor
+        if control.enabled
+            && response.hovered
+            && self
                 .persistent_state
                 .current_input
                 .mouse_position
                 .inside(control.rect)
-            && (self.persistent_state.current_input.primary_down
-                && !self.persistent_state.last_input.primary_down)
         {
-            self.persistent_state.active_control = None;
+            self.output.cursor = CursorIcon::Text;
         }
+        response
+    }
 
-        if (pushed
-            || self
+    pub fn table(
+        &mut self,
+        control: Control,
+        table: Table,
+    ) -> Response<(Option<usize>, Option<TableSort>)> {
+        let control = self.place(control);
+        let rect = control.rect;
+        // Every column owns a uid after the scrollbars, which is used for resizing it
+        let base_uid = control.uid;
+        let column_uid = move |i: usize| base_uid + 3 + i as i64;
+
+        let mut columns: Vec<TableColumn> = table.columns.clone();
+        for (i, column) in columns.iter_mut().enumerate() {
+            let uid = self.sub_control_uid(control.uid, 3 + i as i64);
+            let data = self.get_control_data(uid);
+
+            if self
                 .persistent_state
                 .active_control
-                .is_some_and(|x| x == control.uid))
-            && self.persistent_state.current_input.primary_down
+                .is_some_and(|x| x == uid)
+            {
+                let width = (data.table_column_drag_start_width + self.drag_offset().x)
+                    .max(TABLE_MIN_COLUMN_WIDTH);
+                self.update_control_data(uid, |x| PersistentControlState {
+                    table_column_width: Some(width),
+                    ..x
+                });
+                column.width = width;
+            } else if let Some(width) = data.table_column_width {
+                column.width = width;
+            }
+        }
+        let table = Table {
+            columns: &columns,
+            ..table
+        };
+
+        let content_size = self.styler.table_get_content_size(control, table);
+        let (control, scroll) = self.scrollable(control, content_size);
+
+        let pushed = self.process_push(control);
+        let mut index = table.index;
+        let mut sort = table.sort;
+
+        let mouse_down_hit = self.styler.table_hit_test(
+            control,
+            table,
+            scroll,
+            self.persistent_state.mouse_down_position - control.rect.top_left(),
+        );
+
+        if pushed {
+            if let TableHit::Divider(i) = mouse_down_hit {
+                self.persistent_state.active_control = Some(column_uid(i));
+                self.update_control_data(column_uid(i), |x| PersistentControlState {
+                    table_column_drag_start_width: columns[i].width,
+                    ..x
+                });
+            }
+        }
+
+        // Like buttons, headers are clicked once the mouse is released over the one it was pushed down on
+        if let TableHit::Header(i) = mouse_down_hit {
+            if self.mouse_released(MouseButton::Primary)
+                && self
+                    .persistent_state
+                    .active_control
+                    .is_some_and(|x| x == control.uid)
+                && self.styler.table_hit_test(
+                    control,
+                    table,
+                    scroll,
+                    self.persistent_state.current_input.mouse_position - control.rect.top_left(),
+                ) == mouse_down_hit
+            {
+                // Clicking the sorted column again flips the direction, the caller does the actual sorting
+                let direction = if table
+                    .sort
+                    .is_some_and(|x| x.column == i && x.direction == SortDirection::Ascending)
+                {
+                    SortDirection::Descending
+                } else {
+                    SortDirection::Ascending
+                };
+                sort = Some(TableSort {
+                    column: i,
+                    direction,
+                });
+            }
+        }
+
+        // Only drags which started on a row should change the selection
+        if matches!(mouse_down_hit, TableHit::Row(_))
+            && self
+                .persistent_state
+                .active_control
+                .is_some_and(|x| x == control.uid)
         {
-            let index = self.styler.textbox_index_at_point(
+            if let TableHit::Row(i) = self.styler.table_hit_test(
                 control,
-                textbox,
+                table,
                 scroll,
-                self.persistent_state.current_input.mouse_position,
+                self.persistent_state.current_input.mouse_position - control.rect.top_left(),
+            ) {
+                index = Some(i);
+            }
+        }
+
+        self.push_access_node(
+            control,
+            rect,
+            AccessRole::Table,
+            "",
+            index.and_then(|x| table.rows.get(x)).map(|x| x.join(", ")),
+            None,
+        );
+
+        self.push_screen_clip(control.rect);
+        self.styler.table(control, table, scroll);
+        self.pop_clip();
+
+        self.response(
+            control,
+            rect,
+            pushed,
+            (index, sort),
+            index != table.index || sort != table.sort,
+        )
+    }
+
+    // Visits all tree view rows which aren't hidden inside collapsed nodes in display order
+    // The visitor receives the node, its parent and its depth, and can stop the walk by returning false
+    fn tree_view_walk(
+        &self,
+        uid: i64,
+        model: &dyn TreeModel,
+        mut visitor: impl FnMut(u64, Option<u64>, usize) -> bool,
+    ) {
+        let mut stack: Vec<(u64, Option<u64>, usize)> = model
+            .children(None)
+            .into_iter()
+            .rev()
+            .map(|x| (x, None, 0))
+            .collect();
+
+        while let Some((node, parent, depth)) = stack.pop() {
+            if !visitor(node, parent, depth) {
+                return;
+            }
+
+            // Collapsed nodes are never asked for their children, so huge hidden subtrees cost nothing
+            if self
+                .persistent_state
+                .expanded_tree_nodes
+                .contains(&(uid, node))
+            {
+                stack.extend(
+                    model
+                        .children(Some(node))
+                        .into_iter()
+                        .rev()
+                        .map(|x| (x, Some(node), depth + 1)),
+                );
+            }
+        }
+    }
+
+    // Counting walks all rows, so it's only done again once nodes were expanded or collapsed, or the model changed
+    fn tree_view_row_count(&mut self, uid: i64, model: &dyn TreeModel) -> usize {
+        let revision = model.revision();
+        if let Some((_, count)) = self
+            .get_control_data(uid)
+            .tree_view_row_count
+            .filter(|x| Some(x.0) == revision)
+        {
+            return count;
+        }
+
+        let mut count = 0;
+        self.tree_view_walk(uid, model, |_, _, _| {
+            count += 1;
+            true
+        });
+        self.update_control_data(uid, |x| PersistentControlState {
+            tree_view_row_count: revision.map(|x| (x, count)),
+            ..x
+        });
+        count
+    }
+
+    fn tree_view_toggle(&mut self, uid: i64, node: u64) {
+        if !self
+            .persistent_state
+            .expanded_tree_nodes
+            .remove(&(uid, node))
+        {
+            self.persistent_state
+                .expanded_tree_nodes
+                .insert((uid, node));
+        }
+        self.update_control_data(uid, |x| PersistentControlState {
+            tree_view_row_count: None,
+            ..x
+        });
+    }
+
+    // Gets the node selected after pressing the key, along with the index of its row
+    fn tree_view_navigate(
+        &mut self,
+        uid: i64,
+        model: &dyn TreeModel,
+        selected: Option<u64>,
+        key: Key,
+    ) -> (Option<u64>, Option<usize>) {
+        // Only the rows up to the one after the selected node are needed, unless we jump to the end
+        let mut nodes: Vec<(u64, Option<u64>)> = vec![];
+        let mut found = false;
+        self.tree_view_walk(uid, model, |node, parent, _| {
+            nodes.push((node, parent));
+            if found && key != Key::End {
+                return false;
+            }
+            found = selected.is_none_or(|x| x == node);
+            true
+        });
+
+        if nodes.is_empty() {
+            return (selected, None);
+        }
+
+        let Some(position) = selected.and_then(|x| nodes.iter().position(|y| y.0 == x)) else {
+            return (Some(nodes[0].0), Some(0));
+        };
+        let (node, parent) = nodes[position];
+        let expanded = self
+            .persistent_state
+            .expanded_tree_nodes
+            .contains(&(uid, node));
+
+        let index = match key {
+            Key::Up => position.saturating_sub(1),
+            Key::Down => (position + 1).min(nodes.len() - 1),
+            Key::Home => 0,
+            Key::End => nodes.len() - 1,
+            Key::Enter | Key::Space => {
+                if model.has_children(node) {
+                    self.tree_view_toggle(uid, node);
+                }
+                position
+            }
+            Key::Right => {
+                if expanded {
+                    // The first child directly follows its expanded parent, if there is any
+                    return match nodes.get(position + 1).filter(|x| x.1 == Some(node)) {
+                        Some(child) => (Some(child.0), Some(position + 1)),
+                        None => (Some(node), Some(position)),
+                    };
+                }
+                if model.has_children(node) {
+                    self.tree_view_toggle(uid, node);
+                }
+                position
+            }
+            Key::Left => {
+                if expanded {
+                    self.tree_view_toggle(uid, node);
+                    return (Some(node), Some(position));
+                }
+                // Parents come before their children, so they were walked already
+                parent
+                    .and_then(|x| nodes.iter().position(|y| y.0 == x))
+                    .unwrap_or(position)
+            }
+            _ => position,
+        };
+        (Some(nodes[index].0), Some(index))
+    }
+
+    pub fn tree_view(&mut self, control: Control, tree_view: TreeView) -> Response<Option<u64>> {
+        let control = self.place(control);
+        let rect = control.rect;
+        let initially_selected = tree_view.selected;
+        let mut selected = tree_view.selected;
+
+        // Keys are handled before the scrollbars are placed, so they already show the selection scrolled into view
+        let mut selected_index = None;
+        if control.enabled
+            && self.is_focused(control.uid)
+            && !self.blocked_by_modal()
+            && !self.persistent_state.current_input.pressed_keys.is_empty()
+        {
+            for key in self.persistent_state.current_input.pressed_keys.clone() {
+                (selected, selected_index) =
+                    self.tree_view_navigate(control.uid, tree_view.model, selected, key);
+            }
+        }
+
+        let row_count = self.tree_view_row_count(control.uid, tree_view.model);
+        let content_size = self.styler.tree_view_get_content_size(control, row_count);
+
+        // Scroll the selected row into view, within the space left by a horizontal scrollbar
+        let viewport_height = if content_size.w > control.rect.w {
+            control.rect.h - SCROLLBAR_SIZE
+        } else {
+            control.rect.h
+        };
+        let overflow = content_size.h - viewport_height;
+        if let Some(selected_index) = selected_index.filter(|_| overflow > 0.0) {
+            let row_height = content_size.h / row_count as f32;
+            let row_top = row_height * selected_index as f32;
+            let mut offset = self.get_control_data(control.uid + 2).scrollbar_value * overflow;
+
+            if row_top < offset {
+                offset = row_top;
+            }
+            if row_top + row_height > offset + viewport_height {
+                offset = row_top + row_height - viewport_height;
+            }
+
+            self.update_control_data(control.uid + 2, |x| PersistentControlState {
+                scrollbar_value: (offset / overflow).clamp(0.0, 1.0),
+                ..x
+            });
+        }
+
+        let (control, scroll) = self.scrollable(control, content_size);
+
+        let pushed = self.process_push(control);
+        self.process_focus(control, pushed);
+
+        // Only the rows inside the viewport are materialized
+        let visible_range = self
+            .styler
+            .tree_view_get_visible_range(control, row_count, scroll);
+        let mut rows: Vec<TreeRow> = vec![];
+        let mut index = 0;
+        self.tree_view_walk(control.uid, tree_view.model, |node, _, depth| {
+            if index >= visible_range.1 {
+                return false;
+            }
+            if index >= visible_range.0 {
+                rows.push(TreeRow {
+                    index,
+                    node,
+                    label: tree_view.model.label(node),
+                    depth,
+                    has_children: tree_view.model.has_children(node),
+                    expanded: false,
+                });
+            }
+            index += 1;
+            true
+        });
+        for row in rows.iter_mut() {
+            row.expanded = self
+                .persistent_state
+                .expanded_tree_nodes
+                .contains(&(control.uid, row.node));
+        }
+
+        if pushed {
+            let hit = self.styler.tree_view_hit_test(
+                control,
+                &rows,
+                row_count,
+                scroll,
+                self.persistent_state.mouse_down_position - control.rect.top_left(),
             );
+            let row_at = |index: usize| rows.iter().find(|x| x.index == index);
+
+            match hit {
+                TreeHit::Expander(i) => {
+                    if let Some(row) = row_at(i) {
+                        let node = row.node;
+                        self.tree_view_toggle(control.uid, node);
+                    }
+                }
+                TreeHit::Row(i) => {
+                    if let Some(row) = row_at(i) {
+                        selected = Some(row.node);
+                    }
+                }
+                TreeHit::None => {}
+            }
+        }
+
+        let tree_view = TreeView {
+            selected,
+            ..tree_view
+        };
+        self.push_access_node(
+            control,
+            rect,
+            AccessRole::TreeView,
+            "",
+            selected.map(|x| tree_view.model.label(x)),
+            None,
+        );
+        self.push_screen_clip(control.rect);
+        self.styler
+            .tree_view(control, tree_view, &rows, row_count, scroll);
+        self.pop_clip();
+        self.draw_focus_ring(control);
+
+        self.response(
+            control,
+            rect,
+            pushed,
+            selected,
+            selected != initially_selected,
+        )
+    }
+
+    pub fn begin_scroll_area(&mut self, control: Control) {
+        let control = self.place(control);
+
+        // Content extents are only known once all children were placed, so we go with the last frame's
+        let content_size = self.get_control_data(control.uid).scroll_area_content_size;
+        let (control, scroll) = self.scrollable(control, content_size);
+
+        let offset = Point {
+            x: ((content_size.w - control.rect.w) * scroll.x).max(0.0),
+            y: ((content_size.h - control.rect.h) * scroll.y).max(0.0),
+        };
+        self.push_screen_clip(control.rect);
+        self.scroll_areas.push(ScrollArea {
+            uid: control.uid,
+            origin: control.rect.top_left() - offset,
+            content_size: Size::default(),
+        });
+    }
+
+    pub fn end_scroll_area(&mut self) {
+        let scroll_area = self
+            .scroll_areas
+            .pop()
+            .expect("end_scroll_area called without matching begin_scroll_area");
+
+        self.update_control_data(scroll_area.uid, |x| PersistentControlState {
+            scroll_area_content_size: scroll_area.content_size,
+            ..x
+        });
+        self.pop_clip();
+    }
+
+    // Divides the control into two panes with a divider between them, which can be dragged to resize the panes
+    // Double-clicking the divider collapses the collapsible pane, or shows it again
+    pub fn splitter(&mut self, control: Control, splitter: Splitter) -> Response<SplitterPanes> {
+        let control = self.place(control);
+        let rect = control.rect;
+        let horizontal = splitter.orientation == Orientation::Horizontal;
+        let available = if horizontal { rect.w } else { rect.h };
+        let available = (available - SPLITTER_DIVIDER_SIZE).max(0.0);
+
+        // The limits of both panes end up as limits of the first one's size
+        let min_size = splitter.min_sizes[0].max(available - splitter.max_sizes[1]);
+        let max_size = splitter.max_sizes[0].min(available - splitter.min_sizes[1]);
+        let clamp_size = |size: f32| size.min(max_size).max(min_size).clamp(0.0, available);
+        let collapsed_size = |pane: SplitterPane| match pane {
+            SplitterPane::First => 0.0,
+            SplitterPane::Second => available,
+        };
+
+        let data = self.get_control_data(control.uid);
+        let initial_ratio = data.splitter_ratio.unwrap_or(splitter.ratio);
+        let initial_collapsed = splitter.collapsible.filter(|_| data.splitter_collapsed);
+        let initial_size = clamp_size(initial_ratio * available);
+        let mut size = initial_size;
+        let mut collapsed = initial_collapsed;
+
+        let divider_at = |size: f32| {
+            if horizontal {
+                Rect::new(rect.x + size, rect.y, SPLITTER_DIVIDER_SIZE, rect.h)
+            } else {
+                Rect::new(rect.x, rect.y + size, rect.w, SPLITTER_DIVIDER_SIZE)
+            }
+        };
+        let divider_control = Control {
+            rect: divider_at(collapsed.map_or(size, collapsed_size)),
+            ..control
+        };
+
+        let pushed = self.process_push(divider_control);
+        if pushed {
+            if self.click_count(MouseButton::Primary) == 2 && splitter.collapsible.is_some() {
+                collapsed = match collapsed {
+                    Some(_) => None,
+                    None => splitter.collapsible,
+                };
+            }
             self.update_control_data(control.uid, |x| PersistentControlState {
-                textbox_caret: index.unwrap(),
+                splitter_drag_start_size: collapsed.map_or(size, collapsed_size),
                 ..x
             });
         }
 
-        self.styler.textbox(control, textbox, scroll);
+        // Dragging a collapsed divider shows the pane again, starting from where it was collapsed to
+        let active = self
+            .persistent_state
+            .active_control
+            .is_some_and(|x| x == control.uid);
+        if active && self.persistent_state.dragging {
+            let offset = self.drag_offset();
+            let offset = if horizontal { offset.x } else { offset.y };
+            size = clamp_size(self.get_control_data(control.uid).splitter_drag_start_size + offset);
+            collapsed = None;
+        }
+
+        if let Some(AccessAction::SetValue(new_value)) = self.take_access_action(divider_control) {
+            if let Ok(ratio) = new_value.parse::<f32>() {
+                size = clamp_size(ratio * available);
+                collapsed = None;
+            }
+        }
+
+        let ratio = if available > 0.0 {
+            size / available
+        } else {
+            initial_ratio
+        };
+        self.update_control_data(control.uid, |x| PersistentControlState {
+            splitter_ratio: Some(ratio),
+            splitter_collapsed: collapsed.is_some(),
+            ..x
+        });
+
+        let shown_size = collapsed.map_or(size, collapsed_size);
+        let divider = divider_at(shown_size);
+        let (first, rest) = if horizontal {
+            rect.split_x(shown_size)
+        } else {
+            rect.split_y(shown_size)
+        };
+        let second = if horizontal {
+            rest.split_x(SPLITTER_DIVIDER_SIZE).1
+        } else {
+            rest.split_y(SPLITTER_DIVIDER_SIZE).1
+        };
+        let divider_control = Control {
+            rect: divider,
+            ..control
+        };
+
+        self.push_access_node(
+            divider_control,
+            divider,
+            AccessRole::Splitter,
+            "",
+            None,
+            Some(ratio),
+        );
+        self.styler.splitter(divider_control, splitter);
+
+        let response = self.response(
+            divider_control,
+            divider,
+            pushed,
+            SplitterPanes {
+                first,
+                second,
+                divider,
+                ratio,
+                collapsed,
+            },
+            size != initial_size || collapsed != initial_collapsed,
+        );
+        if control.enabled && (response.hovered || active) {
+            self.output.cursor = if horizontal {
+                CursorIcon::ResizeHorizontal
+            } else {
+                CursorIcon::ResizeVertical
+            };
+        }
+        response
+    }
+
+    // Gets the frontmost window whose visible part as of the last frame contains the point
+    fn window_at(&self, point: Point) -> Option<i64> {
+        self.persistent_state
+            .window_order
+            .iter()
+            .rev()
+            .find(|x| {
+                self.persistent_state
+                    .control_state
+                    .get(x)
+                    .is_some_and(|x| point.inside(x.window_rect))
+            })
+            .copied()
+    }
 
-        text.clone()
+    // Gets the viewport in logical units
+    fn viewport_rect(&self) -> Option<Rect> {
+        self.viewport_size
+            .map(|x| Rect::new(0.0, 0.0, x.w / self.scale, x.h / self.scale))
     }
 
-    pub fn begin(&mut self, input: Input) {
-        self.persistent_state.last_input = self.persistent_state.current_input;
-        self.persistent_state.current_input = input;
+    fn clamp_to_viewport(&self, mut rect: Rect) -> Rect {
+        let Some(viewport) = self.viewport_rect() else {
+            return rect;
+        };
+        rect.w = rect.w.min(viewport.w);
+        rect.h = rect.h.min(viewport.h);
+        rect.x = rect.x.clamp(0.0, viewport.w - rect.w);
+        rect.y = rect.y.clamp(0.0, viewport.h - rect.h);
+        rect
+    }
 
-        if self.persistent_state.current_input.primary_down
-            && !self.persistent_state.last_input.primary_down
+    // Begins a floating window, which is dragged by its title bar and resized by its edges
+    // Controls placed until end_window are positioned relative to the area below the title bar
+    // Windows are drawn on top of everything else, so they have to be placed outside of scroll areas and other windows
+    pub fn begin_window(
+        &mut self,
+        uid: i64,
+        title: &str,
+        rect: &mut Rect,
+    ) -> Response<WindowStatus> {
+        if !self.persistent_state.window_order.contains(&uid) {
+            self.persistent_state.window_order.push(uid);
+        }
+        let layer = self
+            .persistent_state
+            .window_order
+            .iter()
+            .position(|x| *x == uid)
+            .unwrap()
+            + 1;
+        self.window_stack.push(uid);
+        self.windows_this_frame.push(uid);
+        self.styler.set_layer(layer);
+
+        let initial_rect = *rect;
+        let data = self.get_control_data(uid);
+        let mut collapsed = data.window_collapsed;
+        let title_bar = |rect: Rect| Rect::new(rect.x, rect.y, rect.w, WINDOW_TITLE_BAR_HEIGHT);
+
+        let control = Control {
+            uid,
+            enabled: true,
+            rect: if collapsed { title_bar(*rect) } else { *rect },
+        };
+        let pushed = self.process_push(control);
+        if pushed {
+            let drag = WindowDrag::at(*rect, !collapsed, self.persistent_state.mouse_down_position);
+            self.update_control_data(uid, |x| PersistentControlState {
+                window_drag: Some(drag),
+                ..x
+            });
+        }
+
+        let active = self
+            .persistent_state
+            .active_control
+            .is_some_and(|x| x == uid);
+        if active {
+            if let Some(drag) = self.get_control_data(uid).window_drag {
+                *rect = drag.apply(self.drag_offset());
+            }
+        }
+        *rect = self.clamp_to_viewport(*rect);
+
+        // The edges which are being dragged, or would be when pushing down the mouse, show up in the cursor
+        let cursor_drag = if active {
+            self.get_control_data(uid).window_drag
+        } else if self.hovered_window == Some(uid)
+            && self.persistent_state.active_control.is_none()
+            && !self.blocked_by_modal()
         {
+            Some(WindowDrag::at(
+                *rect,
+                !collapsed,
+                self.persistent_state.current_input.mouse_position,
+            ))
+        } else {
+            None
+        };
+        if let Some(cursor) = cursor_drag
+            .map(|x| x.cursor())
+            .filter(|x| *x != CursorIcon::Default)
+        {
+            self.output.cursor = cursor;
+        }
+
+        let button_size = WINDOW_TITLE_BAR_HEIGHT - WINDOW_BUTTON_MARGIN * 2.0;
+        let close_rect = Rect::new(
+            rect.right() - WINDOW_BUTTON_MARGIN - button_size,
+            rect.y + WINDOW_BUTTON_MARGIN,
+            button_size,
+            button_size,
+        );
+        let collapse_rect = close_rect
+            - Point {
+                x: button_size + WINDOW_BUTTON_MARGIN,
+                y: 0.0,
+            };
+
+        self.styler.window(
+            Control {
+                rect: if collapsed { title_bar(*rect) } else { *rect },
+                ..control
+            },
+            Window { title, collapsed },
+            title_bar(*rect),
+        );
+
+        // The buttons own the uids after the window
+        let collapse_uid = self.sub_control_uid(uid, 1);
+        let close_uid = self.sub_control_uid(uid, 2);
+        let collapse_clicked = self
+            .button(
+                Control {
+                    uid: collapse_uid,
+                    enabled: true,
+                    rect: collapse_rect,
+                },
+                Button {
+                    text: if collapsed { "+" } else { "-" },
+                    ..Default::default()
+                },
+            )
+            .clicked;
+        if collapse_clicked {
+            collapsed = !collapsed;
+            self.update_control_data(uid, |x| PersistentControlState {
+                window_collapsed: collapsed,
+                ..x
+            });
+        }
+        let close_clicked = self
+            .button(
+                Control {
+                    uid: close_uid,
+                    enabled: true,
+                    rect: close_rect,
+                },
+                Button {
+                    text: "x",
+                    ..Default::default()
+                },
+            )
+            .clicked;
+
+        let visible_rect = if collapsed { title_bar(*rect) } else { *rect };
+        self.update_control_data(uid, |x| PersistentControlState {
+            window_rect: visible_rect,
+            ..x
+        });
+        self.push_access_node(control, visible_rect, AccessRole::Window, title, None, None);
+        let response = self.response(
+            Control {
+                rect: visible_rect,
+                ..control
+            },
+            visible_rect,
+            pushed,
+            WindowStatus {
+                collapsed,
+                close_clicked,
+            },
+            *rect != initial_rect || collapse_clicked,
+        );
+
+        // Content goes inside the border, and is clipped away entirely while collapsed
+        let content_rect = if collapsed {
+            Rect::new(rect.x, rect.y + WINDOW_TITLE_BAR_HEIGHT, 0.0, 0.0)
+        } else {
+            Rect::new(
+                rect.x + 1.0,
+                rect.y + WINDOW_TITLE_BAR_HEIGHT,
+                rect.w - 2.0,
+                rect.h - WINDOW_TITLE_BAR_HEIGHT - 1.0,
+            )
+        };
+        self.push_screen_clip(content_rect);
+        self.scroll_areas.push(ScrollArea {
+            uid,
+            origin: content_rect.top_left(),
+            content_size: Size::default(),
+        });
+        response
+    }
+
+    pub fn end_window(&mut self) {
+        self.window_stack
+            .pop()
+            .expect("end_window called without matching begin_window");
+        self.scroll_areas.pop();
+        self.pop_clip();
+        self.styler.set_layer(0);
+    }
+
+    // Begins a window which is kept in front of all others and blocks input to everything outside of it
+    // Everything below it is dimmed, and the blocking ends with the first frame it isn't placed in
+    pub fn begin_modal(
+        &mut self,
+        uid: i64,
+        title: &str,
+        rect: &mut Rect,
+    ) -> Response<WindowStatus> {
+        self.modal_this_frame = Some(uid);
+        self.persistent_state.window_order.retain(|x| *x != uid);
+        self.persistent_state.window_order.push(uid);
+
+        // The overlay goes below the modal, but above all other windows
+        self.styler
+            .set_layer(self.persistent_state.window_order.len());
+        self.styler
+            .modal_overlay(self.viewport_rect().unwrap_or(Rect::new(
+                0.0,
+                0.0,
+                MODAL_OVERLAY_FALLBACK_SIZE,
+                MODAL_OVERLAY_FALLBACK_SIZE,
+            )));
+        self.begin_window(uid, title, rect)
+    }
+
+    pub fn end_modal(&mut self) {
+        self.end_window();
+    }
+
+    // Shows a modal message box each frame it's placed in, until the choice is returned once a button was clicked
+    // Closing the box or pressing escape counts as cancelling, or as OK if that's the only button
+    pub fn message_box(
+        &mut self,
+        uid: i64,
+        title: &str,
+        text: &str,
+        buttons: MessageBoxButtons,
+    ) -> Option<MessageBoxResult> {
+        let mut rect = self
+            .get_control_data(uid)
+            .message_box_rect
+            .unwrap_or_else(|| {
+                let viewport = self.viewport_rect().unwrap_or_default();
+                Rect::new(
+                    ((viewport.w - MESSAGE_BOX_WIDTH) / 2.0).max(0.0),
+                    ((viewport.h - MESSAGE_BOX_HEIGHT) / 2.0).max(0.0),
+                    MESSAGE_BOX_WIDTH,
+                    MESSAGE_BOX_HEIGHT,
+                )
+            });
+
+        let status = self.begin_modal(uid, title, &mut rect).value;
+        let mut result = None;
+        if status.close_clicked || self.key_pressed(Key::Escape) {
+            result = Some(buttons.dismissed());
+        }
+
+        // The label and buttons own the uids after the window's buttons
+        let content_w = rect.w - 2.0;
+        let content_h = rect.h - WINDOW_TITLE_BAR_HEIGHT - 1.0;
+        let buttons_y = content_h - MESSAGE_BOX_MARGIN - MESSAGE_BOX_BUTTON_HEIGHT;
+        let label_uid = self.sub_control_uid(uid, 3);
+        self.label(
+            Control {
+                uid: label_uid,
+                enabled: true,
+                rect: Rect::new(
+                    MESSAGE_BOX_MARGIN,
+                    MESSAGE_BOX_MARGIN,
+                    content_w - MESSAGE_BOX_MARGIN * 2.0,
+                    buttons_y - MESSAGE_BOX_MARGIN * 2.0,
+                ),
+            },
+            Label { text },
+        );
+
+        let choices = buttons.choices();
+        for (i, (choice, text)) in choices.iter().enumerate() {
+            // Buttons are aligned to the right, in the order of the choices
+            let offset_from_right = (choices.len() - i) as f32;
+            let x = content_w - (MESSAGE_BOX_BUTTON_WIDTH + MESSAGE_BOX_MARGIN) * offset_from_right;
+            let button_uid = self.sub_control_uid(uid, 4 + i as i64);
+            if self
+                .button(
+                    Control {
+                        uid: button_uid,
+                        enabled: true,
+                        rect: Rect::new(
+                            x,
+                            buttons_y,
+                            MESSAGE_BOX_BUTTON_WIDTH,
+                            MESSAGE_BOX_BUTTON_HEIGHT,
+                        ),
+                    },
+                    Button {
+                        text,
+                        ..Default::default()
+                    },
+                )
+                .clicked
+            {
+                result = Some(*choice);
+            }
+        }
+        self.end_modal();
+
+        // The next message box with this uid starts out centered again
+        let message_box_rect = if result.is_some() { None } else { Some(rect) };
+        self.update_control_data(uid, |x| PersistentControlState {
+            message_box_rect,
+            ..x
+        });
+        result
+    }
+
+    fn push_screen_clip(&mut self, rect: Rect) {
+        let clip = match self.clip_stack.last() {
+            Some(parent) => rect.intersect(*parent),
+            None => rect,
+        };
+        self.clip_stack.push(clip);
+        self.styler.set_clip(Some(clip));
+    }
+
+    // Restricts drawing and interaction of all following controls to the specified rect, until the matching pop_clip
+    pub fn push_clip(&mut self, rect: Rect) {
+        let rect = match self.scroll_areas.last() {
+            Some(scroll_area) => rect + scroll_area.origin,
+            None => rect,
+        };
+        self.push_screen_clip(rect);
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.clip_stack
+            .pop()
+            .expect("pop_clip called without matching push_clip");
+        self.styler.set_clip(self.clip_stack.last().copied());
+    }
+
+    pub fn begin(&mut self, mut input: Input) {
+        #[cfg(feature = "recording")]
+        if let Some(recording) = &mut self.recording {
+            recording.frames.push(RecordedFrame {
+                input: input.clone(),
+                scale: self.scale,
+                viewport_size: self.viewport_size,
+                access_actions: self.queued_access_actions.clone(),
+                values: vec![],
+            });
+        }
+
+        input.mouse_position = input.mouse_position * (1.0 / self.scale);
+
+        self.persistent_state.last_input =
+            std::mem::replace(&mut self.persistent_state.current_input, input);
+        // Animations shouldn't skip ahead after the host didn't run any frames for a while
+        self.persistent_state.frame_delta = (self.persistent_state.current_input.time
+            - self.persistent_state.last_input.time)
+            .clamp(0.0, MAX_FRAME_DELTA) as f32;
+
+        // Unbalanced scroll areas, windows or clips from the last frame mustn't leak into this one
+        self.scroll_areas.clear();
+        self.window_stack.clear();
+        self.clip_stack.clear();
+        self.focus_order.clear();
+        self.click_claimed = false;
+        self.wheel_target = self.hovered_scrollable.take();
+        self.output = FrameOutput::default();
+        self.debug_controls.clear();
+        self.access_nodes.clear();
+
+        // Focus applies to the frame as a whole, all other actions are consumed by their control
+        self.access_actions = std::mem::take(&mut self.queued_access_actions);
+        for (uid, action) in &self.access_actions {
+            if *action == AccessAction::Focus {
+                self.persistent_state.focused_control = Some(*uid);
+            }
+        }
+        self.access_actions.retain(|x| x.1 != AccessAction::Focus);
+
+        let input = &self.persistent_state.current_input;
+        let last_input = &self.persistent_state.last_input;
+        for button in MouseButton::ALL {
+            let down = input.button_down(button);
+            let was_down = last_input.button_down(button);
+            let state = &mut self.persistent_state.buttons[button as usize];
+            state.pressed = down && !was_down;
+            state.released = !down && was_down;
+
+            if state.pressed {
+                let repeated = state.click_count > 0
+                    && (0.0..=MULTI_CLICK_TIME).contains(&(input.time - state.down_time))
+                    && input.mouse_position.dist(state.down_position) <= MULTI_CLICK_DISTANCE;
+                state.click_count = if repeated { state.click_count + 1 } else { 1 };
+                state.down_position = input.mouse_position;
+                state.down_time = input.time;
+            }
+        }
+
+        if self.mouse_pressed(MouseButton::Primary) {
             self.persistent_state.mouse_down_position =
                 self.persistent_state.current_input.mouse_position;
+            self.persistent_state.dragging = false;
+        }
+
+        // Pushing down the mouse anywhere in a window brings it to the front, unless a modal blocks it
+        self.hovered_window = self.window_at(self.persistent_state.current_input.mouse_position);
+        if self.mouse_pressed(MouseButton::Primary) {
+            self.pressed_window = self.window_at(self.persistent_state.mouse_down_position);
+            if let Some(uid) = self
+                .pressed_window
+                .filter(|x| self.modal.is_none_or(|y| y == *x))
+            {
+                self.persistent_state.window_order.retain(|x| *x != uid);
+                self.persistent_state.window_order.push(uid);
+            }
+        }
+        self.persistent_state.hovered_layer = match self.hovered_window {
+            _ if self.modal.is_some() && self.hovered_window != self.modal => None,
+            Some(uid) => self
+                .persistent_state
+                .window_order
+                .iter()
+                .position(|x| *x == uid)
+                .map(|x| x + 1),
+            None => Some(0),
+        };
+        self.modal_this_frame = None;
+
+        self.drag_started = false;
+        if self.persistent_state.current_input.primary_down
+            && !self.persistent_state.dragging
+            && self
+                .persistent_state
+                .current_input
+                .mouse_position
+                .dist(self.persistent_state.mouse_down_position)
+                > self.drag_threshold
+        {
+            self.persistent_state.dragging = true;
+            self.drag_started = true;
         }
 
         self.styler.begin(self.persistent_state.clone());
     }
 
-    pub fn end(&mut self) {
+    fn process_tab_navigation(&mut self) {
+        // Clicking into empty space takes the focus away from everything
+        if self.mouse_pressed(MouseButton::Primary) && !self.click_claimed {
+            self.persistent_state.focused_control = None;
+        }
+
+        // Controls which weren't placed this frame or got disabled can't keep the focus
+        let mut position = self
+            .persistent_state
+            .focused_control
+            .and_then(|x| self.focus_order.iter().position(|y| *y == x));
+        if position.is_none() {
+            self.persistent_state.focused_control = None;
+        }
+
+        if self.focus_order.is_empty() {
+            return;
+        }
+
+        let len = self.focus_order.len();
+        let backwards = self.persistent_state.current_input.modifiers.shift;
+        for key in &self.persistent_state.current_input.pressed_keys {
+            if *key != Key::Tab {
+                continue;
+            }
+            position = Some(match (position, backwards) {
+                (Some(x), false) => (x + 1) % len,
+                (Some(x), true) => (x + len - 1) % len,
+                (None, false) => 0,
+                (None, true) => len - 1,
+            });
+        }
+
+        self.persistent_state.focused_control = position.map(|x| self.focus_order[x]);
+    }
+
+    // Discards the state of controls which weren't placed for a while, along with their expanded tree nodes
+    fn collect_garbage(&mut self) {
+        let frame = self.persistent_state.frame;
+        let retention = self.state_retention_frames;
+        let control_state = &self.persistent_state.control_state;
+
+        // Sub-controls are kept along with their pinned parent, and parents have lower uids, so the walk always ends
+        let pinned = |mut uid: i64| loop {
+            if self.pinned_controls.contains(&uid) {
+                return true;
+            }
+            match control_state.get(&uid).and_then(|x| x.parent) {
+                Some(parent) => uid = parent,
+                None => return false,
+            }
+        };
+        let expired: Vec<i64> = control_state
+            .iter()
+            .filter(|(uid, state)| frame - state.last_seen_frame > retention && !pinned(**uid))
+            .map(|(uid, _)| *uid)
+            .collect();
+        for uid in expired {
+            self.persistent_state.control_state.remove(&uid);
+        }
+
+        let control_state = &self.persistent_state.control_state;
+        self.persistent_state
+            .expanded_tree_nodes
+            .retain(|(uid, _)| control_state.contains_key(uid));
+    }
+
+    // Gets what the host should do now that the frame was drawn
+    pub fn end(&mut self) -> FrameOutput {
+        if self.debug_overlay {
+            self.draw_debug_overlay();
+        }
         self.styler.end();
+        if let Some(time) = self.styler.repaint_at() {
+            self.request_repaint_at(time);
+        }
+
+        let focused_control = self.persistent_state.focused_control;
+        self.process_tab_navigation();
+        self.collect_garbage();
+
+        // Windows which weren't placed are gone, so they can't be hit by the mouse anymore
+        let windows = std::mem::take(&mut self.windows_this_frame);
+        self.persistent_state
+            .window_order
+            .retain(|uid| windows.contains(uid));
+        let modal = self.modal_this_frame.take();
+        self.persistent_state.frame += 1;
+
+        // The focus can't stay on a control below a modal which just opened
+        if modal.is_some() && modal != self.modal {
+            self.persistent_state.focused_control = None;
+        }
 
         // As soon as we let go of the primary mouse button, the active control should be cleared
+        let mut active_cleared = false;
         if self.persistent_state.active_control.is_some()
             && !self.persistent_state.current_input.primary_down
-            && self.persistent_state.clear_active_control_after_mouse_up
         {
             self.persistent_state.active_control = None;
+            active_cleared = true;
+        }
+
+        // These changes only show up when the controls are drawn again
+        if active_cleared
+            || modal != self.modal
+            || focused_control != self.persistent_state.focused_control
+        {
+            self.output.repaint = true;
         }
+        self.modal = modal;
+        self.output
     }
 }
@@ -0,0 +1,33 @@
+use crate::geo::Rect;
+
+// The mouse cursor the host should show, as requested by the control under the mouse or being dragged
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CursorIcon {
+    #[default]
+    Default,
+    // An I-beam, shown over text which can be edited
+    Text,
+    // Resizing along the x axis
+    ResizeHorizontal,
+    // Resizing along the y axis
+    ResizeVertical,
+    // Resizing from the top left or bottom right corner
+    ResizeNwSe,
+    // Resizing from the top right or bottom left corner
+    ResizeNeSw,
+}
+
+// What the host should do after a frame, as requested by the controls placed during it
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameOutput {
+    pub cursor: CursorIcon,
+    // Whether the host should deliver text input, e.g. by showing an on-screen keyboard
+    pub text_input: bool,
+    // Where the caret of the focused textbox is, in physical pixels like the input
+    // IMEs show their candidate window next to it
+    pub ime_rect: Option<Rect>,
+    // Whether the next frame would look different even without new input, so it should be run right away
+    pub repaint: bool,
+    // Time of the input clock at which a frame should be run even without new input, e.g. for a caret blink
+    pub repaint_at: Option<f64>,
+}
@@ -0,0 +1,66 @@
+// A color with 8 bits per channel, which is independent of any rendering backend
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    // Mixes the colors channel by channel, getting self at 0 and other at 1
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: mix(self.a, other.a),
+        }
+    }
+}
+
+// A rect in physical pixels
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PixelRect {
+    pub x: i32,
+    pub y: i32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl PixelRect {
+    pub fn new(x: i32, y: i32, w: u32, h: u32) -> PixelRect {
+        PixelRect { x, y, w, h }
+    }
+    pub fn right(&self) -> i32 {
+        self.x + self.w as i32
+    }
+    pub fn bottom(&self) -> i32 {
+        self.y + self.h as i32
+    }
+}
+
+// The primitives a rendering backend has to provide for the StandardStyler to draw with
+// Everything is specified in physical pixels
+pub trait Painter {
+    fn clear(&mut self, color: Color);
+    fn fill_rect(&mut self, rect: PixelRect, color: Color);
+    // Restricts drawing to the rect, or lifts the restriction when passed None
+    fn set_clip(&mut self, rect: Option<PixelRect>);
+    // Sets the pixel size text is measured and drawn at
+    fn set_font_size(&mut self, size: u16);
+    // Measures a single line of text, where the height is the font's line height
+    fn text_size(&self, text: &str) -> (u32, u32);
+    // Draws a single line of text with its top-left corner at the position
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, color: Color);
+    // Shows everything drawn since the last clear
+    fn present(&mut self);
+}
@@ -0,0 +1,97 @@
+use crate::access::AccessAction;
+use crate::geo::Size;
+use crate::input::Input;
+use crate::styler::Styler;
+use crate::Ugui;
+use serde::{Deserialize, Serialize};
+
+// Bumped whenever the file format changes, so stale recordings are rejected instead of misinterpreted
+const RECORDING_VERSION: u32 = 2;
+
+// The value a control returned, formatted with Debug so controls with any value type can be compared
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedValue {
+    pub uid: i64,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    // The input exactly as it was passed to begin, which includes the frame's timestamp
+    pub input: Input,
+    pub scale: f32,
+    pub viewport_size: Option<Size>,
+    // Actions from assistive technology which were applied during the frame
+    pub access_actions: Vec<(i64, AccessAction)>,
+    // Values of all controls in the order they were placed
+    pub values: Vec<RecordedValue>,
+}
+
+// A session of frames, which can be replayed to reproduce the same UI states
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    pub fn save(&self, mut writer: impl std::io::Write) -> Result<(), String> {
+        bincode::serialize_into(&mut writer, &RECORDING_VERSION).map_err(|e| e.to_string())?;
+        bincode::serialize_into(writer, self).map_err(|e| e.to_string())
+    }
+
+    pub fn load(mut reader: impl std::io::Read) -> Result<Recording, String> {
+        let version: u32 = bincode::deserialize_from(&mut reader).map_err(|e| e.to_string())?;
+        if version != RECORDING_VERSION {
+            return Err(format!(
+                "Recording has version {}, but only version {} is supported",
+                version, RECORDING_VERSION
+            ));
+        }
+        bincode::deserialize_from(reader).map_err(|e| e.to_string())
+    }
+
+    // Feeds the recorded input into the ugui, calling frame between begin and end to place the same controls as when recording
+    // Fails with a description of the first frame whose control values differ from the recorded ones
+    // The replay is recorded to compare it, so it's refused while the ugui is already recording
+    // The state the recording started from isn't part of it, so the ugui has to be a new one which didn't run any frames
+    pub fn replay<T: Styler>(
+        &self,
+        ugui: &mut Ugui<T>,
+        mut frame: impl FnMut(&mut Ugui<T>),
+    ) -> Result<(), String> {
+        if ugui.is_recording() {
+            return Err("Can't replay while the ugui is recording".to_string());
+        }
+        if ugui.persistent_state.frame != 0 {
+            return Err("Can't replay into a ugui which already ran frames".to_string());
+        }
+
+        ugui.start_recording();
+        for recorded_frame in &self.frames {
+            if ugui.scale() != recorded_frame.scale {
+                ugui.set_scale(recorded_frame.scale);
+            }
+            // Recordings made without a viewport size are replayed without one as well
+            ugui.viewport_size = recorded_frame.viewport_size;
+            for (uid, action) in &recorded_frame.access_actions {
+                ugui.queue_access_action(*uid, action.clone());
+            }
+            ugui.begin(recorded_frame.input.clone());
+            frame(ugui);
+            ugui.end();
+        }
+        let replayed = ugui.stop_recording().unwrap_or_default();
+
+        for (i, (recorded_frame, replayed_frame)) in
+            self.frames.iter().zip(&replayed.frames).enumerate()
+        {
+            if recorded_frame.values != replayed_frame.values {
+                return Err(format!(
+                    "Frame {} diverged, recorded {:?} but replayed {:?}",
+                    i, recorded_frame.values, replayed_frame.values
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::geo::{Point, Rect};
+
+// What happened to a control during the frame, along with the control's resulting value
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Response<T = ()> {
+    pub value: T,
+    // The mouse is over the visible part of the control and no other control is being interacted with
+    pub hovered: bool,
+    // The control was pushed down this frame
+    pub pressed: bool,
+    // The control was pushed down earlier and the mouse got released over it this frame
+    pub clicked: bool,
+    // The control was pushed down by the second or third click of a quick succession of clicks
+    pub double_clicked: bool,
+    pub triple_clicked: bool,
+    // Both pushing and releasing the secondary mouse button happened over the control
+    pub secondary_clicked: bool,
+    // The value differs from the one passed into the control
+    pub changed: bool,
+    // Mouse movement since the last frame while the control is active
+    pub drag_delta: Point,
+    pub gained_focus: bool,
+    pub lost_focus: bool,
+    // The control's bounds in screen space
+    pub rect: Rect,
+}
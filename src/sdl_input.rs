@@ -0,0 +1,143 @@
+use crate::geo::Point;
+use crate::input::{Input, InputAccumulator, Key, Modifiers, MouseButton};
+use crate::output::FrameOutput;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod, TextInputUtil};
+use sdl2::mouse::{MouseButton as SdlMouseButton, MouseWheelDirection};
+use std::time::Duration;
+
+// Builds the Input for each frame out of the SDL events received since the last one
+pub struct InputCollector {
+    input: InputAccumulator,
+    text_input: TextInputUtil,
+}
+
+fn map_key(keycode: Keycode) -> Option<Key> {
+    match keycode {
+        Keycode::Up => Some(Key::Up),
+        Keycode::Down => Some(Key::Down),
+        Keycode::Left => Some(Key::Left),
+        Keycode::Right => Some(Key::Right),
+        Keycode::Home => Some(Key::Home),
+        Keycode::End => Some(Key::End),
+        Keycode::PageUp => Some(Key::PageUp),
+        Keycode::PageDown => Some(Key::PageDown),
+        Keycode::Return | Keycode::KpEnter => Some(Key::Enter),
+        Keycode::Space => Some(Key::Space),
+        Keycode::Tab => Some(Key::Tab),
+        Keycode::Backspace => Some(Key::Backspace),
+        Keycode::Delete => Some(Key::Delete),
+        Keycode::Escape => Some(Key::Escape),
+        _ => None,
+    }
+}
+
+fn map_modifiers(keymod: Mod) -> Modifiers {
+    Modifiers {
+        shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+        alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+        super_key: keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD),
+    }
+}
+
+impl InputCollector {
+    pub fn new(text_input: TextInputUtil) -> InputCollector {
+        // SDL starts out with text input enabled, but it should only be on while a textbox is focused
+        text_input.stop();
+        InputCollector {
+            input: InputAccumulator::new(),
+            text_input,
+        }
+    }
+
+    pub fn process_event(&mut self, event: &Event) {
+        match *event {
+            Event::MouseMotion { x, y, .. } => {
+                self.input.mouse_moved(Point {
+                    x: x as f32,
+                    y: y as f32,
+                });
+            }
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            }
+            | Event::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => {
+                self.input.mouse_moved(Point {
+                    x: x as f32,
+                    y: y as f32,
+                });
+                let button = match mouse_btn {
+                    SdlMouseButton::Left => MouseButton::Primary,
+                    SdlMouseButton::Right => MouseButton::Secondary,
+                    SdlMouseButton::Middle => MouseButton::Middle,
+                    _ => return,
+                };
+                self.input
+                    .button_changed(button, matches!(event, Event::MouseButtonDown { .. }));
+            }
+            Event::MouseWheel { y, direction, .. } => {
+                // With natural scrolling, SDL reports the wheel inverted and tells us through the direction
+                let y = if direction == MouseWheelDirection::Flipped {
+                    -y
+                } else {
+                    y
+                };
+                self.input.wheel_turned(y as f64);
+            }
+            Event::KeyDown {
+                keycode, keymod, ..
+            } => {
+                self.input.modifiers_changed(map_modifiers(keymod));
+                if let Some(key) = keycode.and_then(map_key) {
+                    self.input.key_pressed(key);
+                }
+            }
+            Event::KeyUp { keymod, .. } => {
+                self.input.modifiers_changed(map_modifiers(keymod));
+            }
+            Event::TextInput { ref text, .. } => {
+                self.input.text_typed(text);
+            }
+            Event::Window { win_event, .. } => match win_event {
+                WindowEvent::Leave => self.input.mouse_left(),
+                WindowEvent::FocusLost => self.input.focus_lost(),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // Gets how long it is until the time of the input clock, e.g. to wait for FrameOutput::repaint_at
+    pub fn time_until(&self, time: f64) -> Duration {
+        self.input.time_until(time)
+    }
+
+    // Gets the input for the next call to Ugui::begin, consuming everything which is only reported once
+    pub fn take_input(&mut self) -> Input {
+        self.input.take_input()
+    }
+
+    // Starts or stops SDL text input, which also shows IME candidate windows and on-screen keyboards
+    // Should be called with the output of each frame
+    pub fn apply_output(&mut self, output: &FrameOutput) {
+        if let Some(rect) = output.ime_rect {
+            self.text_input.set_rect(sdl2::rect::Rect::new(
+                rect.x as i32,
+                rect.y as i32,
+                rect.w.ceil() as u32,
+                rect.h.ceil() as u32,
+            ));
+        }
+        if output.text_input == self.text_input.is_active() {
+            return;
+        }
+        if output.text_input {
+            self.text_input.start();
+        } else {
+            self.text_input.stop();
+        }
+    }
+}
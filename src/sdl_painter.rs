@@ -0,0 +1,117 @@
+use crate::geo::{Point, Rect};
+use crate::painter::{Color, Painter, PixelRect};
+use sdl2::pixels::Color as SdlColor;
+use sdl2::rect::Point as SdlPoint;
+use sdl2::rect::Rect as SdlRect;
+use sdl2::render::WindowCanvas;
+use sdl2::ttf::{Font, Sdl2TtfContext};
+use std::path::{Path, PathBuf};
+
+// Draws onto an SDL window canvas, rendering text with SDL_ttf
+pub struct SdlPainter<'a> {
+    canvas: WindowCanvas,
+    ttf_context: &'a Sdl2TtfContext,
+    font_path: PathBuf,
+    font: Font<'a, 'static>,
+}
+
+impl From<Point> for SdlPoint {
+    fn from(point: Point) -> SdlPoint {
+        SdlPoint::new(point.x as i32, point.y as i32)
+    }
+}
+
+impl From<Rect> for SdlRect {
+    fn from(rect: Rect) -> SdlRect {
+        SdlRect::new(rect.x as i32, rect.y as i32, rect.w as u32, rect.h as u32)
+    }
+}
+
+impl From<Color> for SdlColor {
+    fn from(color: Color) -> SdlColor {
+        SdlColor::RGBA(color.r, color.g, color.b, color.a)
+    }
+}
+
+impl From<PixelRect> for SdlRect {
+    fn from(rect: PixelRect) -> SdlRect {
+        SdlRect::new(rect.x, rect.y, rect.w, rect.h)
+    }
+}
+
+impl<'a> SdlPainter<'a> {
+    pub fn new(
+        mut canvas: WindowCanvas,
+        ttf_context: &'a Sdl2TtfContext,
+        font_path: &Path,
+    ) -> Result<Self, String> {
+        // The styler picks the real size right away, this only checks that the font can be loaded
+        let font = ttf_context.load_font(font_path, 12)?;
+        // Translucent colors, like the one of the modal overlay, are blended with what's below
+        canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
+
+        Ok(Self {
+            canvas,
+            ttf_context,
+            font_path: font_path.to_path_buf(),
+            font,
+        })
+    }
+}
+
+impl<'a> Painter for SdlPainter<'a> {
+    fn clear(&mut self, color: Color) {
+        self.canvas.set_draw_color(color);
+        self.canvas.clear();
+    }
+
+    fn fill_rect(&mut self, rect: PixelRect, color: Color) {
+        if rect.w == 0 || rect.h == 0 {
+            return;
+        }
+        self.canvas.set_draw_color(color);
+        self.canvas.fill_rect(SdlRect::from(rect)).unwrap();
+    }
+
+    fn set_clip(&mut self, rect: Option<PixelRect>) {
+        // SDL can't represent empty rects, so we move those off-screen instead
+        let sdl_rect = rect.map(|x| {
+            if x.w == 0 || x.h == 0 {
+                SdlRect::new(-1, -1, 1, 1)
+            } else {
+                SdlRect::from(x)
+            }
+        });
+        self.canvas.set_clip_rect(sdl_rect);
+    }
+
+    fn set_font_size(&mut self, size: u16) {
+        let ttf_context = self.ttf_context;
+        self.font = ttf_context.load_font(&self.font_path, size).unwrap();
+    }
+
+    fn text_size(&self, text: &str) -> (u32, u32) {
+        self.font.size_of(text).unwrap()
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, color: Color) {
+        // SDL freaks out when performing operations on 0-width strings
+        if text.is_empty() {
+            return;
+        }
+
+        let surface = self.font.render(text).blended(color).unwrap();
+        let texture_creator = self.canvas.texture_creator();
+        let texture = texture_creator
+            .create_texture_from_surface(&surface)
+            .unwrap();
+
+        // The texture is copied at its exact size, since stretching it would make it blurry
+        let destination = SdlRect::new(x, y, surface.width(), surface.height());
+        self.canvas.copy(&texture, None, Some(destination)).unwrap();
+    }
+
+    fn present(&mut self) {
+        self.canvas.present();
+    }
+}
@@ -0,0 +1,162 @@
+use crate::painter::{Color, Painter, PixelRect};
+use fontdue::{Font, FontSettings, Metrics};
+use std::collections::HashMap;
+
+// Draws into a framebuffer in memory, rendering text with fontdue
+// Doesn't depend on any windowing system, so hosts copy the pixels wherever they need them
+pub struct SoftwarePainter {
+    width: u32,
+    height: u32,
+    // One 0RGB pixel per u32, row by row
+    pixels: Vec<u32>,
+    clip: Option<PixelRect>,
+    font: Font,
+    font_size: f32,
+    // Rasterized glyphs at the current font size
+    glyphs: HashMap<char, (Metrics, Vec<u8>)>,
+}
+
+impl SoftwarePainter {
+    pub fn new(font_data: &[u8]) -> Result<Self, String> {
+        let font = Font::from_bytes(font_data, FontSettings::default())?;
+
+        Ok(Self {
+            width: 0,
+            height: 0,
+            pixels: vec![],
+            clip: None,
+            font,
+            font_size: 12.0,
+            glyphs: HashMap::new(),
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u32] {
+        &self.pixels
+    }
+
+    // Changes the framebuffer's size, which discards its contents
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.pixels = vec![0; (width * height) as usize];
+    }
+
+    // Gets the drawable area as left, top, right and bottom edges
+    fn bounds(&self) -> (i32, i32, i32, i32) {
+        let mut bounds = (0, 0, self.width as i32, self.height as i32);
+        if let Some(clip) = self.clip {
+            bounds.0 = bounds.0.max(clip.x);
+            bounds.1 = bounds.1.max(clip.y);
+            bounds.2 = bounds.2.min(clip.right());
+            bounds.3 = bounds.3.min(clip.bottom());
+        }
+        bounds
+    }
+}
+
+// Blends the color over the pixel, where coverage scales the color's alpha
+fn blend(pixel: &mut u32, color: Color, coverage: u8) {
+    let alpha = color.a as u32 * coverage as u32 / 255;
+    if alpha == 0 {
+        return;
+    }
+
+    let old = *pixel;
+    let mix = |shift: u32, channel: u8| {
+        let old = (old >> shift) & 0xFF;
+        (channel as u32 * alpha + old * (255 - alpha)) / 255
+    };
+    *pixel = mix(16, color.r) << 16 | mix(8, color.g) << 8 | mix(0, color.b);
+}
+
+impl Painter for SoftwarePainter {
+    fn clear(&mut self, color: Color) {
+        let pixel = (color.r as u32) << 16 | (color.g as u32) << 8 | color.b as u32;
+        self.pixels.fill(pixel);
+    }
+
+    fn fill_rect(&mut self, rect: PixelRect, color: Color) {
+        let (left, top, right, bottom) = self.bounds();
+        for y in rect.y.max(top)..rect.bottom().min(bottom) {
+            for x in rect.x.max(left)..rect.right().min(right) {
+                blend(
+                    &mut self.pixels[(y * self.width as i32 + x) as usize],
+                    color,
+                    255,
+                );
+            }
+        }
+    }
+
+    fn set_clip(&mut self, rect: Option<PixelRect>) {
+        self.clip = rect;
+    }
+
+    fn set_font_size(&mut self, size: u16) {
+        self.font_size = size as f32;
+        self.glyphs.clear();
+    }
+
+    fn text_size(&self, text: &str) -> (u32, u32) {
+        let width: f32 = text
+            .chars()
+            .map(|x| self.font.metrics(x, self.font_size).advance_width)
+            .sum();
+        let height = self
+            .font
+            .horizontal_line_metrics(self.font_size)
+            .map_or(self.font_size, |x| x.new_line_size);
+
+        (width.ceil() as u32, height.ceil() as u32)
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, color: Color) {
+        let ascent = self
+            .font
+            .horizontal_line_metrics(self.font_size)
+            .map_or(self.font_size, |x| x.ascent);
+        let baseline = y + ascent.round() as i32;
+        let (left, top, right, bottom) = self.bounds();
+
+        let mut pen = x as f32;
+        for c in text.chars() {
+            let font_size = self.font_size;
+            let font = &self.font;
+            let (metrics, bitmap) = self
+                .glyphs
+                .entry(c)
+                .or_insert_with(|| font.rasterize(c, font_size));
+
+            let glyph_x = pen.round() as i32 + metrics.xmin;
+            let glyph_y = baseline - metrics.height as i32 - metrics.ymin;
+            for row in 0..metrics.height as i32 {
+                for column in 0..metrics.width as i32 {
+                    let (px, py) = (glyph_x + column, glyph_y + row);
+                    if px < left || px >= right || py < top || py >= bottom {
+                        continue;
+                    }
+                    let coverage = bitmap[(row * metrics.width as i32 + column) as usize];
+                    blend(
+                        &mut self.pixels[(py * self.width as i32 + px) as usize],
+                        color,
+                        coverage,
+                    );
+                }
+            }
+
+            pen += metrics.advance_width;
+        }
+    }
+
+    // The framebuffer is read through pixels, so there's nothing to present
+    fn present(&mut self) {}
+}
@@ -1,20 +1,21 @@
-use crate::control::{Button, Control, Listbox, Scrollbar, Textbox};
-use crate::geo::{remap, Point, Rect};
+use crate::animation::Animations;
+use crate::control::{
+    Button, Control, Label, Listbox, Orientation, Scrollbar, SortDirection, Splitter, Table,
+    TableHit, Textbox, TreeHit, TreeRow, TreeView, Window,
+};
+use crate::debug::{DebugOverlay, DrawStats};
+use crate::geo::{remap, Point, Rect, Size};
+use crate::painter::{Color, Painter, PixelRect};
 use crate::standard_styler::VisualState::{Active, Disabled, Hover, Normal};
 use crate::styler::Styler;
 use crate::PersistentState;
-use sdl2::pixels::Color;
-use sdl2::rect::Rect as SdlRect;
-use sdl2::render::WindowCanvas;
-use sdl2::ttf::{Font, Sdl2TtfContext};
 use std::collections::HashMap;
-use std::path::Path;
 
 fn hex(str: &str) -> Color {
     let r = &str[1..3];
     let g = &str[3..5];
     let b = &str[5..7];
-    Color::RGB(
+    Color::rgb(
         u8::from_str_radix(r, 16).unwrap(),
         u8::from_str_radix(g, 16).unwrap(),
         u8::from_str_radix(b, 16).unwrap(),
@@ -39,12 +40,37 @@ const LISTBOX_ITEM_HEIGHT: f32 = 20.0;
 const TEXTBOX_PADDING: f32 = 2.0;
 const FONT_SIZE: f32 = 12.0;
 const LINE_HEIGHT: f32 = 16.0;
+const TABLE_HEADER_HEIGHT: f32 = 24.0;
+const TABLE_ROW_HEIGHT: f32 = 20.0;
+const TABLE_CELL_PADDING: f32 = 4.0;
+const TABLE_DIVIDER_GRAB_WIDTH: f32 = 4.0;
+const TABLE_SORT_INDICATOR_SIZE: f32 = 4.0;
+const TREE_VIEW_ROW_HEIGHT: f32 = 20.0;
+const TREE_VIEW_INDENT: f32 = 16.0;
+const TREE_VIEW_EXPANDER_SIZE: f32 = 9.0;
+const WINDOW_TITLE_PADDING: f32 = 6.0;
+
+// Drawing sent to a layer above the base one, which is held back until everything below it was drawn
+enum PaintCommand {
+    FillRect(PixelRect, Color),
+    SetClip(Option<PixelRect>),
+    DrawText(String, i32, i32, Color),
+}
 
-pub struct StandardStyler<'a> {
-    canvas: WindowCanvas,
-    _ttf_context: &'a Sdl2TtfContext,
-    font: Font<'a, 'static>,
+fn execute<P: Painter>(painter: &mut P, command: PaintCommand) {
+    match command {
+        PaintCommand::FillRect(rect, color) => painter.fill_rect(rect, color),
+        PaintCommand::SetClip(rect) => painter.set_clip(rect),
+        PaintCommand::DrawText(text, x, y, color) => painter.draw_text(&text, x, y, color),
+    }
+}
+
+pub struct StandardStyler<P: Painter> {
+    pub painter: P,
+    // Factor between logical units, which all metrics and controls are specified in, and physical pixels
+    scale: f32,
     persistent_state: PersistentState,
+    clip: Option<Rect>,
     button_back_colors: HashMap<VisualState, Color>,
     button_border_colors: HashMap<VisualState, Color>,
     button_text_colors: HashMap<VisualState, Color>,
@@ -55,15 +81,33 @@ pub struct StandardStyler<'a> {
     textbox_back_colors: HashMap<VisualState, Color>,
     textbox_border_colors: HashMap<VisualState, Color>,
     textbox_text_colors: HashMap<VisualState, Color>,
+    textbox_selection_color: Color,
     scrollbar_back_colors: HashMap<VisualState, Color>,
     scrollbar_thumb_colors: HashMap<VisualState, Color>,
+    table_header_back_colors: HashMap<VisualState, Color>,
+    table_header_border_colors: HashMap<VisualState, Color>,
+    table_header_text_colors: HashMap<VisualState, Color>,
+    tree_view_guide_colors: HashMap<VisualState, Color>,
+    tree_view_expander_colors: HashMap<VisualState, Color>,
+    focus_ring_color: Color,
+    window_back_color: Color,
+    window_border_color: Color,
+    window_title_back_color: Color,
+    window_title_text_color: Color,
+    label_text_colors: HashMap<VisualState, Color>,
+    splitter_back_colors: HashMap<VisualState, Color>,
+    splitter_line_color: Color,
+    modal_overlay_color: Color,
+    draw_stats: DrawStats,
+    animations: Animations,
+    layer: usize,
+    // Commands of all layers above the base one, which is drawn immediately
+    layers: Vec<Vec<PaintCommand>>,
 }
 
-impl<'a> StandardStyler<'a> {
-    pub fn new(canvas: WindowCanvas, ttf_context: &'a Sdl2TtfContext) -> Self {
-        let font = ttf_context
-            .load_font(Path::new("examples/fonts/segoe.ttf"), FONT_SIZE as u16)
-            .unwrap();
+impl<P: Painter> StandardStyler<P> {
+    pub fn new(mut painter: P) -> Self {
+        painter.set_font_size(FONT_SIZE as u16);
 
         let mut button_back_colors = HashMap::new();
         button_back_colors.insert(Normal, hex("#E1E1E1"));
@@ -83,6 +127,18 @@ impl<'a> StandardStyler<'a> {
         button_text_colors.insert(Active, hex("#000000"));
         button_text_colors.insert(Disabled, hex("#A0A0A0"));
 
+        let mut label_text_colors = HashMap::new();
+        label_text_colors.insert(Normal, hex("#000000"));
+        label_text_colors.insert(Hover, hex("#000000"));
+        label_text_colors.insert(Active, hex("#000000"));
+        label_text_colors.insert(Disabled, hex("#A0A0A0"));
+
+        let mut splitter_back_colors = HashMap::new();
+        splitter_back_colors.insert(Normal, hex("#F0F0F0"));
+        splitter_back_colors.insert(Hover, hex("#E0E0E0"));
+        splitter_back_colors.insert(Active, hex("#CDCDCD"));
+        splitter_back_colors.insert(Disabled, hex("#F0F0F0"));
+
         let mut listbox_back_colors = HashMap::new();
         listbox_back_colors.insert(Normal, hex("#FFFFFF"));
         listbox_back_colors.insert(Hover, hex("#FFFFFF"));
@@ -137,11 +193,41 @@ impl<'a> StandardStyler<'a> {
         scrollbar_thumb_colors.insert(Active, hex("#606060"));
         scrollbar_thumb_colors.insert(Disabled, hex("#C0C0C0"));
 
+        let mut table_header_back_colors = HashMap::new();
+        table_header_back_colors.insert(Normal, hex("#FFFFFF"));
+        table_header_back_colors.insert(Hover, hex("#D9EBF9"));
+        table_header_back_colors.insert(Active, hex("#BCDCF4"));
+        table_header_back_colors.insert(Disabled, hex("#FFFFFF"));
+
+        let mut table_header_border_colors = HashMap::new();
+        table_header_border_colors.insert(Normal, hex("#E5E5E5"));
+        table_header_border_colors.insert(Hover, hex("#E5E5E5"));
+        table_header_border_colors.insert(Active, hex("#E5E5E5"));
+        table_header_border_colors.insert(Disabled, hex("#E5E5E5"));
+
+        let mut table_header_text_colors = HashMap::new();
+        table_header_text_colors.insert(Normal, hex("#000000"));
+        table_header_text_colors.insert(Hover, hex("#000000"));
+        table_header_text_colors.insert(Active, hex("#000000"));
+        table_header_text_colors.insert(Disabled, hex("#A0A0A0"));
+
+        let mut tree_view_guide_colors = HashMap::new();
+        tree_view_guide_colors.insert(Normal, hex("#E0E0E0"));
+        tree_view_guide_colors.insert(Hover, hex("#E0E0E0"));
+        tree_view_guide_colors.insert(Active, hex("#C0C0C0"));
+        tree_view_guide_colors.insert(Disabled, hex("#F0F0F0"));
+
+        let mut tree_view_expander_colors = HashMap::new();
+        tree_view_expander_colors.insert(Normal, hex("#7A7A7A"));
+        tree_view_expander_colors.insert(Hover, hex("#0078D7"));
+        tree_view_expander_colors.insert(Active, hex("#005499"));
+        tree_view_expander_colors.insert(Disabled, hex("#CCCCCC"));
+
         Self {
             persistent_state: Default::default(),
-            canvas,
-            _ttf_context: ttf_context,
-            font,
+            clip: None,
+            painter,
+            scale: 1.0,
             button_back_colors,
             button_border_colors,
             button_text_colors,
@@ -152,16 +238,123 @@ impl<'a> StandardStyler<'a> {
             textbox_back_colors,
             textbox_border_colors,
             textbox_text_colors,
+            textbox_selection_color: hex("#CCE8FF"),
             scrollbar_back_colors,
             scrollbar_thumb_colors,
+            table_header_back_colors,
+            table_header_border_colors,
+            table_header_text_colors,
+            tree_view_guide_colors,
+            tree_view_expander_colors,
+            focus_ring_color: hex("#000000"),
+            window_back_color: hex("#F0F0F0"),
+            window_border_color: hex("#7A7A7A"),
+            window_title_back_color: hex("#FFFFFF"),
+            window_title_text_color: hex("#000000"),
+            label_text_colors,
+            splitter_back_colors,
+            splitter_line_color: hex("#CDCDCD"),
+            modal_overlay_color: Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 96,
+            },
+            draw_stats: Default::default(),
+            animations: Default::default(),
+            layer: 0,
+            layers: vec![],
         }
     }
 
+    // Converts a rect from logical units to physical pixels
+    // The edges are rounded individually, so adjacent rects don't end up with gaps between them
+    fn to_physical(&self, rect: Rect) -> PixelRect {
+        let x = (rect.x * self.scale).round();
+        let y = (rect.y * self.scale).round();
+        let right = (rect.right() * self.scale).round();
+        let bottom = (rect.bottom() * self.scale).round();
+
+        PixelRect::new(
+            x as i32,
+            y as i32,
+            (right - x).max(0.0) as u32,
+            (bottom - y).max(0.0) as u32,
+        )
+    }
+
+    fn paint(&mut self, command: PaintCommand) {
+        if self.layer == 0 {
+            execute(&mut self.painter, command);
+        } else {
+            self.layers[self.layer - 1].push(command);
+        }
+    }
+
+    fn fill(&mut self, rect: Rect, color: Color) {
+        if rect.w <= 0.0 || rect.h <= 0.0 {
+            return;
+        }
+        self.paint(PaintCommand::FillRect(self.to_physical(rect), color));
+        self.draw_stats.draw_calls += 1;
+    }
+
     fn quad(&mut self, rect: Rect, back_color: Color, border_color: Color) {
-        self.canvas.set_draw_color(border_color);
-        self.canvas.fill_rect(rect.to_sdl()).unwrap();
-        self.canvas.set_draw_color(back_color);
-        self.canvas.fill_rect(rect.inflate(-1.0).to_sdl()).unwrap();
+        self.fill(rect, border_color);
+        self.fill(rect.inflate(-1.0), back_color);
+    }
+
+    // Draws a horizontal or vertical line including both end points, which is one unit thick regardless of the scale
+    fn line(&mut self, from: Point, to: Point, color: Color) {
+        let rect = Rect::new(
+            from.x.min(to.x),
+            from.y.min(to.y),
+            (to.x - from.x).abs() + 1.0,
+            (to.y - from.y).abs() + 1.0,
+        );
+        self.fill(rect, color);
+    }
+
+    // Draws only the outline of a rect, which is used to restore borders after drawing scrolled content over them
+    fn border(&mut self, rect: Rect, color: Color) {
+        self.line(
+            rect.top_left(),
+            rect.top_right() - Point { x: 1.0, y: 0.0 },
+            color,
+        );
+        self.line(
+            rect.bottom_left() - Point { x: 0.0, y: 1.0 },
+            rect.bottom_right() - Point { x: 1.0, y: 1.0 },
+            color,
+        );
+        self.line(
+            rect.top_left(),
+            rect.bottom_left() - Point { x: 0.0, y: 1.0 },
+            color,
+        );
+        self.line(
+            rect.top_right() - Point { x: 1.0, y: 0.0 },
+            rect.bottom_right() - Point { x: 1.0, y: 1.0 },
+            color,
+        );
+    }
+
+    // Measures text in logical units, as the font is rasterized at the physical size
+    fn text_size(&self, text: &str) -> Size {
+        let size = self.painter.text_size(text);
+
+        Size {
+            w: size.0 as f32 / self.scale,
+            h: size.1 as f32 / self.scale,
+        }
+    }
+
+    // Shrinks a rect down to the part which isn't clipped away
+    fn visible_rect(&self, rect: Rect) -> Rect {
+        match self.clip {
+            Some(clip) => rect.intersect(clip),
+            None => rect,
+        }
     }
 
     fn get_multiline_string_positions(&mut self, text: &str) -> Vec<(usize, Point)> {
@@ -175,28 +368,25 @@ impl<'a> StandardStyler<'a> {
             line += " ";
 
             // Compute bounds of current line
-            let size = self.font.size_of(&line).unwrap();
-            let text_size = Point {
-                x: size.0 as f32,
-                y: size.1 as f32,
-            };
+            let text_size = self.text_size(&line);
             let line_rect = Rect {
                 x: 0.0,
                 y: LINE_HEIGHT * i as f32,
-                w: text_size.x,
+                w: text_size.w,
                 h: LINE_HEIGHT,
             };
 
-            for j in 0..line.len() {
+            // Indices are byte offsets, so only char boundaries get a position
+            for (j, _) in line.char_indices() {
                 positions.push((
-                    char_count,
+                    char_count + j,
                     Point {
-                        x: self.font.size_of(&line[0..j]).unwrap().0 as f32,
+                        x: self.painter.text_size(&line[0..j]).0 as f32 / self.scale,
                         y: line_rect.y,
                     },
                 ));
-                char_count += 1;
             }
+            char_count += line.len();
         }
         return positions;
     }
@@ -214,7 +404,8 @@ impl<'a> StandardStyler<'a> {
     fn position_in_multiline_string(&mut self, text: &str, index: usize) -> Point {
         let positions = self.get_multiline_string_positions(text);
 
-        return positions.iter().find(|x| x.0 == index).unwrap().1;
+        // Indices kept in control state can be past the end of text which the caller shortened since
+        return positions.iter().rev().find(|x| x.0 <= index).unwrap().1;
     }
 
     fn draw_text(
@@ -225,35 +416,12 @@ impl<'a> StandardStyler<'a> {
         horizontal_alignment: Alignment,
         vertical_alignment: Alignment,
     ) {
-        let texture_creator = self.canvas.texture_creator();
-
         let lines = text.split("\n").collect::<Vec<&str>>();
 
         for i in 0..lines.len() {
-            let mut line = lines[i].replace("\n", "");
-
-            // SDL freaks out when performing operations on 0-width strings
-            if line.len() == 0 {
-                line = " ".to_string();
-            }
-
-            let surface = self
-                .font
-                .render(&line)
-                .blended(color)
-                .map_err(|e| e.to_string())
-                .unwrap();
-
-            let texture = texture_creator
-                .create_texture_from_surface(&surface)
-                .map_err(|e| e.to_string())
-                .unwrap();
+            let line = lines[i].replace("\n", "");
 
-            let size = self.font.size_of(&line).unwrap();
-            let text_size = Point {
-                x: size.0 as f32,
-                y: size.1 as f32,
-            };
+            let text_size = self.text_size(&line);
             let mut line_rect = Rect {
                 x: rect.x,
                 y: rect.y + (i as f32 * LINE_HEIGHT),
@@ -265,22 +433,26 @@ impl<'a> StandardStyler<'a> {
                 line_rect = rect;
             }
             if horizontal_alignment == Alignment::Center {
-                line_rect.x += line_rect.w / 2.0 - text_size.x / 2.0;
+                line_rect.x += line_rect.w / 2.0 - text_size.w / 2.0;
             }
             if horizontal_alignment == Alignment::End {
-                line_rect.x += line_rect.w - text_size.x;
+                line_rect.x += line_rect.w - text_size.w;
             }
             if vertical_alignment == Alignment::Center {
-                line_rect.y += line_rect.h / 2.0 - text_size.y / 2.0;
+                line_rect.y += line_rect.h / 2.0 - text_size.h / 2.0;
             }
             if vertical_alignment == Alignment::End {
-                line_rect.y += line_rect.h - text_size.y;
+                line_rect.y += line_rect.h - text_size.h;
             }
-            line_rect.w = text_size.x;
-            line_rect.h = text_size.y;
-            self.canvas
-                .copy(&texture, None, Some(line_rect.to_sdl()))
-                .unwrap();
+
+            self.paint(PaintCommand::DrawText(
+                line,
+                (line_rect.x * self.scale).round() as i32,
+                (line_rect.y * self.scale).round() as i32,
+                color,
+            ));
+            self.draw_stats.draw_calls += 1;
+            self.draw_stats.text_renders += 1;
         }
     }
 
@@ -289,12 +461,23 @@ impl<'a> StandardStyler<'a> {
             return Disabled;
         }
 
+        // Clipped away parts of the control can't be hovered
+        let control = Control {
+            rect: self.visible_rect(control.rect),
+            ..control
+        };
+
         if self.persistent_state.active_control.is_some()
             && self.persistent_state.active_control.unwrap() == control.uid
         {
             return Active;
         }
 
+        // Controls on layers below the hovered one are covered by something else
+        if self.persistent_state.hovered_layer != Some(self.layer) {
+            return Normal;
+        }
+
         let now_inside = self
             .persistent_state
             .current_input
@@ -388,34 +571,285 @@ impl<'a> StandardStyler<'a> {
     }
 
     fn get_visible_range(
-        &self,
-        control: Control,
-        listbox: Listbox,
-        scroll: Point,
+        item_count: usize,
+        item_height: f32,
+        viewport_height: f32,
+        offset: f32,
     ) -> (usize, usize) {
-        let content_height = listbox.items.len() as f32 * LISTBOX_ITEM_HEIGHT;
+        let index_begin = (offset / item_height) as usize;
+        let index_end = ((viewport_height + offset) / item_height) as usize + 1;
+
+        (
+            index_begin.clamp(0, item_count),
+            index_end.clamp(0, item_count),
+        )
+    }
+
+    // Shortens the text and appends an ellipsis until it fits into the specified width
+    fn ellipsize(&self, text: &str, max_width: f32) -> String {
+        if text.is_empty() || self.text_size(text).w <= max_width {
+            return text.to_string();
+        }
+
+        for (i, _) in text.char_indices().rev() {
+            let candidate = format!("{}…", &text[..i]);
+            if self.text_size(&candidate).w <= max_width {
+                return candidate;
+            }
+        }
+
+        String::new()
+    }
+
+    fn table_get_offset(&self, control: Control, table: Table, scroll: Point) -> Point {
+        let content_size = self.table_get_content_size(control, table);
+
+        Point {
+            x: ((content_size.w - control.rect.w) * scroll.x).max(0.0),
+            y: ((content_size.h - control.rect.h) * scroll.y).max(0.0),
+        }
+    }
+
+    fn table_header_visual_state(&self, enabled: bool, rect: Rect) -> VisualState {
+        if !enabled {
+            return Disabled;
+        }
+
+        let rect = self.visible_rect(rect);
+        let input = &self.persistent_state.current_input;
+        let now_inside = input.mouse_position.inside(rect);
+        let down_inside = self.persistent_state.mouse_down_position.inside(rect);
+
+        if now_inside && input.primary_down && down_inside {
+            return Active;
+        }
 
-        let mut index_begin =
-            ((scroll.y * (content_height - control.rect.h)) / LISTBOX_ITEM_HEIGHT) as usize;
-        let mut index_end = ((control.rect.h + (scroll.y * (content_height - control.rect.h)))
-            / LISTBOX_ITEM_HEIGHT) as usize
-            + 1;
+        if now_inside && !input.primary_down {
+            return Hover;
+        }
+
+        Normal
+    }
+
+    fn tree_view_get_offset(&self, control: Control, row_count: usize, scroll: Point) -> f32 {
+        let content_size = self.tree_view_get_content_size(control, row_count);
+
+        ((content_size.h - control.rect.h) * scroll.y).max(0.0)
+    }
 
-        return (
-            index_begin.clamp(0, listbox.items.len()),
-            index_end.clamp(0, listbox.items.len()),
+    fn tree_view_expander(&mut self, rect: Rect, expanded: bool, color: Color) {
+        let back_color = *self.listbox_back_colors.get(&Normal).unwrap();
+        self.quad(rect, back_color, color);
+
+        let center = Point {
+            x: rect.x + (rect.w / 2.0).floor(),
+            y: rect.y + (rect.h / 2.0).floor(),
+        };
+        let arm = (rect.w / 2.0).floor() - 2.0;
+
+        self.line(
+            center - Point { x: arm, y: 0.0 },
+            center + Point { x: arm, y: 0.0 },
+            color,
         );
+        if !expanded {
+            self.line(
+                center - Point { x: 0.0, y: arm },
+                center + Point { x: 0.0, y: arm },
+                color,
+            );
+        }
+    }
+
+    fn sort_indicator(&mut self, rect: Rect, direction: SortDirection, color: Color) {
+        let center = Point {
+            x: rect.right() - TABLE_CELL_PADDING - TABLE_SORT_INDICATOR_SIZE,
+            y: rect.y + rect.h / 2.0 - TABLE_SORT_INDICATOR_SIZE / 2.0,
+        };
+
+        for i in 0..TABLE_SORT_INDICATOR_SIZE as i32 {
+            // Ascending arrows point up, so their rows widen from top to bottom
+            let half_width = match direction {
+                SortDirection::Ascending => i,
+                SortDirection::Descending => TABLE_SORT_INDICATOR_SIZE as i32 - 1 - i,
+            } as f32;
+            let y = center.y + i as f32;
+
+            self.line(
+                Point {
+                    x: center.x - half_width,
+                    y,
+                },
+                Point {
+                    x: center.x + half_width,
+                    y,
+                },
+                color,
+            );
+        }
     }
 }
 
-impl<'a> Styler for StandardStyler<'a> {
+impl<P: Painter> Styler for StandardStyler<P> {
     fn begin(&mut self, persistent_state: PersistentState) {
+        self.animations.begin(
+            persistent_state.current_input.time,
+            persistent_state.frame_delta,
+        );
         self.persistent_state = persistent_state;
-        self.canvas.set_draw_color(Color::RGB(253, 253, 253));
-        self.canvas.clear();
+        self.draw_stats = Default::default();
+        self.layer = 0;
+        self.set_clip(None);
+        self.painter.clear(Color::rgb(253, 253, 253));
+    }
+    fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+
+        // Fonts are rasterized at their final pixel size, since scaling the rendered text would make it blurry
+        self.painter
+            .set_font_size((FONT_SIZE * scale).round() as u16);
+    }
+    fn set_clip(&mut self, rect: Option<Rect>) {
+        self.clip = rect;
+        let pixel_rect = rect.map(|x| self.to_physical(x));
+        self.paint(PaintCommand::SetClip(pixel_rect));
+    }
+    fn set_layer(&mut self, layer: usize) {
+        if self.layers.len() < layer {
+            self.layers.resize_with(layer, Vec::new);
+        }
+        self.layer = layer;
     }
     fn end(&mut self) {
-        self.canvas.present();
+        self.animations.end();
+        for layer in std::mem::take(&mut self.layers) {
+            self.painter.set_clip(None);
+            for command in layer {
+                execute(&mut self.painter, command);
+            }
+        }
+        self.painter.present();
+    }
+    fn repaint_at(&self) -> Option<f64> {
+        self.animations.repaint_at()
+    }
+    fn draw_stats(&self) -> DrawStats {
+        self.draw_stats
+    }
+    fn debug_overlay(&mut self, overlay: &DebugOverlay) {
+        let text_color = hex("#FFFFFF");
+
+        for control in &overlay.controls {
+            let color = if control.collides {
+                hex("#E81123")
+            } else if overlay.active_control == Some(control.uid) {
+                hex("#0078D7")
+            } else if overlay.hovered_control == Some(control.uid) {
+                hex("#FF8C00")
+            } else {
+                hex("#B4009E")
+            };
+            self.border(control.rect, color);
+
+            let label = control.uid.to_string();
+            let label_rect = Rect::new(
+                control.rect.x,
+                control.rect.y,
+                self.text_size(&label).w + 4.0,
+                LINE_HEIGHT,
+            );
+            self.fill(label_rect, color);
+            self.draw_text(
+                &label,
+                label_rect,
+                text_color,
+                Alignment::Center,
+                Alignment::Center,
+            );
+        }
+
+        let uid_text = |uid: Option<i64>| uid.map_or("none".to_string(), |x| x.to_string());
+        let mut text = format!(
+            "active: {}\nhovered: {}\nfocused: {}\ndraw calls: {}, text renders: {}",
+            uid_text(overlay.active_control),
+            uid_text(overlay.hovered_control),
+            uid_text(overlay.focused_control),
+            overlay.draw_stats.draw_calls,
+            overlay.draw_stats.text_renders,
+        );
+        if let Some(hovered_state) = &overlay.hovered_state {
+            text += "\n";
+            text += hovered_state;
+        }
+
+        let lines = text.split('\n').collect::<Vec<&str>>();
+        let width = lines
+            .iter()
+            .map(|x| self.text_size(x).w)
+            .fold(0.0, f32::max);
+        let panel_rect = Rect::new(
+            0.0,
+            0.0,
+            width + 8.0,
+            lines.len() as f32 * LINE_HEIGHT + 8.0,
+        );
+        self.fill(panel_rect, hex("#202020"));
+        self.draw_text(
+            &text,
+            panel_rect.inflate(-4.0),
+            text_color,
+            Alignment::Start,
+            Alignment::Start,
+        );
+    }
+
+    fn window(&mut self, control: Control, window: Window, title_bar: Rect) {
+        let back_color = self.window_back_color;
+        let border_color = self.window_border_color;
+        let title_back_color = self.window_title_back_color;
+        let title_text_color = self.window_title_text_color;
+
+        self.quad(control.rect, back_color, border_color);
+        self.fill(title_bar.inflate(-1.0), title_back_color);
+
+        // The collapse and close buttons take up a square each at the end of the title bar
+        let text_rect = Rect::new(
+            title_bar.x + WINDOW_TITLE_PADDING,
+            title_bar.y,
+            title_bar.w - title_bar.h * 2.0 - WINDOW_TITLE_PADDING,
+            title_bar.h,
+        );
+        let title = self.ellipsize(window.title, text_rect.w);
+        self.draw_text(
+            &title,
+            text_rect,
+            title_text_color,
+            Alignment::Start,
+            Alignment::Center,
+        );
+    }
+
+    fn modal_overlay(&mut self, rect: Rect) {
+        let color = self.modal_overlay_color;
+        self.fill(rect, color);
+    }
+
+    fn focus_ring(&mut self, control: Control) {
+        let color = self.focus_ring_color;
+        self.border(control.rect.inflate(-2.0), color);
+    }
+
+    fn label(&mut self, control: Control, label: Label) {
+        let visual_state = self.get_visual_state(control);
+        let text_color = *self.label_text_colors.get(&visual_state).unwrap();
+
+        self.draw_text(
+            label.text,
+            control.rect,
+            text_color,
+            Alignment::Start,
+            Alignment::Start,
+        );
     }
 
     fn button(&mut self, control: Control, button: Button) {
@@ -428,6 +862,10 @@ impl<'a> Styler for StandardStyler<'a> {
             .clone();
         let text_color = self.button_text_colors.get(&visual_state).unwrap().clone();
 
+        // Hovering and pushing fades between the colors, while the text stays readable throughout
+        let back_color = self.animations.fade(control.uid, 0, back_color);
+        let border_color = self.animations.fade(control.uid, 1, border_color);
+
         self.quad(control.rect, back_color, border_color);
         self.draw_text(
             button.text,
@@ -439,7 +877,7 @@ impl<'a> Styler for StandardStyler<'a> {
     }
 
     fn scrollbar(&mut self, control: Control, scrollbar: Scrollbar) {
-        let thumb_rect = StandardStyler::scrollbar_get_thumb(control, scrollbar);
+        let thumb_rect = Self::scrollbar_get_thumb(control, scrollbar);
 
         // We need visual state of thumb, not scrollbar, since thats the interactable part
         let visual_state = self.get_visual_state(Control {
@@ -462,6 +900,21 @@ impl<'a> Styler for StandardStyler<'a> {
         self.quad(thumb_rect, thumb_color, thumb_color);
     }
 
+    fn splitter(&mut self, control: Control, splitter: Splitter) {
+        let visual_state = self.get_visual_state(control);
+        let back_color = *self.splitter_back_colors.get(&visual_state).unwrap();
+        let line_color = self.splitter_line_color;
+
+        // A line along the middle of the divider shows where the panes meet
+        let rect = control.rect;
+        let line_rect = match splitter.orientation {
+            Orientation::Horizontal => Rect::new(rect.center().x.floor(), rect.y, 1.0, rect.h),
+            Orientation::Vertical => Rect::new(rect.x, rect.center().y.floor(), rect.w, 1.0),
+        };
+        self.fill(rect, back_color);
+        self.fill(line_rect, line_color);
+    }
+
     fn listbox(&mut self, control: Control, listbox: Listbox, scroll: Point) {
         let visual_state = self.get_visual_state(control);
         let back_color = self.listbox_back_colors.get(&visual_state).unwrap().clone();
@@ -473,24 +926,25 @@ impl<'a> Styler for StandardStyler<'a> {
 
         self.quad(control.rect, back_color, border_color);
 
-        let prev_clip_rect = self.canvas.clip_rect();
-        self.canvas
-            .set_clip_rect(control.rect.inflate(-1.0).to_sdl());
-
-        let visible_range = self.get_visible_range(control, listbox, scroll);
         let content_size = self.listbox_get_content_size(control, listbox);
+        let visible_range = Self::get_visible_range(
+            listbox.items.len(),
+            LISTBOX_ITEM_HEIGHT,
+            control.rect.h,
+            scroll.y * (content_size.h - control.rect.h),
+        );
 
-        let x_offset = ((content_size.x - control.rect.w) * scroll.x).max(0.0);
+        let x_offset = ((content_size.w - control.rect.w) * scroll.x).max(0.0);
 
         for i in visible_range.0..visible_range.1 {
             let base_y = LISTBOX_ITEM_HEIGHT * i as f32;
-            let moved_y = scroll.y * (content_size.y - control.rect.h);
+            let moved_y = scroll.y * (content_size.h - control.rect.h);
             let final_y = base_y - moved_y;
 
             let rect = Rect::new(
                 control.rect.x - x_offset,
                 control.rect.y + final_y,
-                content_size.x.max(control.rect.w),
+                content_size.w.max(control.rect.w),
                 LISTBOX_ITEM_HEIGHT,
             )
             .inflate(-1.0);
@@ -502,7 +956,7 @@ impl<'a> Styler for StandardStyler<'a> {
             );
         }
 
-        self.canvas.set_clip_rect(prev_clip_rect);
+        self.border(control.rect, border_color);
     }
 
     fn listbox_index_at_point(
@@ -517,7 +971,7 @@ impl<'a> Styler for StandardStyler<'a> {
         }
         let content_size = self.listbox_get_content_size(control, listbox);
 
-        let index = (((point.y + (scroll.y * (content_size.y - control.rect.h)))
+        let index = (((point.y + (scroll.y * (content_size.h - control.rect.h)))
             / LISTBOX_ITEM_HEIGHT)
             .ceil()
             - 1.0) as usize;
@@ -525,23 +979,319 @@ impl<'a> Styler for StandardStyler<'a> {
         Some(index.clamp(0, listbox.items.len() - 1))
     }
 
-    fn listbox_get_content_size(&self, control: Control, listbox: Listbox) -> Point {
+    fn listbox_get_content_size(&self, control: Control, listbox: Listbox) -> Size {
         // Width is measured by getting max width of all items
         // TODO: Optimize, as this is very slow on large data sets
-        let item_widths = listbox
-            .items
-            .iter()
-            .map(|x| self.font.size_of(x).unwrap().0);
+        let item_widths = listbox.items.iter().map(|x| self.text_size(x).w);
 
-        return Point {
+        return Size {
             // We add the padding back in because it's off otherwise
-            x: item_widths.max().unwrap() as f32 + LISTBOX_ITEM_PADDING,
-            y: listbox.items.len() as f32 * LISTBOX_ITEM_HEIGHT,
+            w: item_widths.fold(0.0, f32::max) + LISTBOX_ITEM_PADDING,
+            h: listbox.items.len() as f32 * LISTBOX_ITEM_HEIGHT,
         };
     }
 
-    fn textbox(&mut self, control: Control, textbox: Textbox, scroll: Point) {
+    fn table(&mut self, control: Control, table: Table, scroll: Point) {
         let visual_state = self.get_visual_state(control);
+        let back_color = *self.listbox_back_colors.get(&visual_state).unwrap();
+        let border_color = *self.listbox_border_colors.get(&visual_state).unwrap();
+
+        self.quad(control.rect, back_color, border_color);
+
+        let content_size = self.table_get_content_size(control, table);
+        let offset = self.table_get_offset(control, table, scroll);
+        let visible_range = Self::get_visible_range(
+            table.rows.len(),
+            TABLE_ROW_HEIGHT,
+            control.rect.h - TABLE_HEADER_HEIGHT,
+            offset.y,
+        );
+
+        for i in visible_range.0..visible_range.1 {
+            let mut item_visual_state = Normal;
+            if !control.enabled {
+                item_visual_state = Disabled;
+            }
+            if table.index.is_some_and(|x| x == i) {
+                item_visual_state = Active;
+            }
+            let item_back_color = *self
+                .listbox_item_back_colors
+                .get(&item_visual_state)
+                .unwrap();
+            let item_text_color = *self
+                .listbox_item_text_colors
+                .get(&item_visual_state)
+                .unwrap();
+
+            let row_rect = Rect::new(
+                control.rect.x - offset.x,
+                control.rect.y + TABLE_HEADER_HEIGHT + TABLE_ROW_HEIGHT * i as f32 - offset.y,
+                content_size.w.max(control.rect.w),
+                TABLE_ROW_HEIGHT,
+            )
+            .inflate(-1.0);
+            self.quad(row_rect, item_back_color, item_back_color);
+
+            let mut x = control.rect.x - offset.x;
+            for (j, column) in table.columns.iter().enumerate() {
+                let cell_rect =
+                    Rect::new(x, row_rect.y, column.width, row_rect.h).inflate(-TABLE_CELL_PADDING);
+                x += column.width;
+
+                let Some(cell) = table.rows[i].get(j) else {
+                    continue;
+                };
+                let text = self.ellipsize(cell, cell_rect.w);
+                self.draw_text(
+                    &text,
+                    cell_rect,
+                    item_text_color,
+                    Alignment::Start,
+                    Alignment::Center,
+                );
+            }
+        }
+
+        // The header is drawn last, as it stays in place while the rows scroll underneath it
+        let header_rect = Rect::new(
+            control.rect.x,
+            control.rect.y,
+            control.rect.w,
+            TABLE_HEADER_HEIGHT,
+        );
+        let header_back_color = *self.table_header_back_colors.get(&Normal).unwrap();
+        let header_border_color = *self.table_header_border_colors.get(&Normal).unwrap();
+        self.quad(header_rect, header_back_color, header_border_color);
+
+        let mut x = control.rect.x - offset.x;
+        for (i, column) in table.columns.iter().enumerate() {
+            let cell_rect = Rect::new(x, control.rect.y, column.width, TABLE_HEADER_HEIGHT);
+            x += column.width;
+
+            let header_visual_state = self.table_header_visual_state(control.enabled, cell_rect);
+            let back_color = *self
+                .table_header_back_colors
+                .get(&header_visual_state)
+                .unwrap();
+            let border_color = *self
+                .table_header_border_colors
+                .get(&header_visual_state)
+                .unwrap();
+            let text_color = *self
+                .table_header_text_colors
+                .get(&header_visual_state)
+                .unwrap();
+
+            self.quad(cell_rect, back_color, border_color);
+
+            let mut text_rect = cell_rect.inflate(-TABLE_CELL_PADDING);
+            if let Some(sort) = table.sort.filter(|x| x.column == i) {
+                self.sort_indicator(cell_rect, sort.direction, text_color);
+                text_rect.w -= TABLE_SORT_INDICATOR_SIZE * 2.0 + TABLE_CELL_PADDING;
+            }
+
+            let text = self.ellipsize(column.header, text_rect.w);
+            self.draw_text(
+                &text,
+                text_rect,
+                text_color,
+                Alignment::Start,
+                Alignment::Center,
+            );
+        }
+
+        self.border(control.rect, border_color);
+    }
+
+    fn table_get_content_size(&self, _control: Control, table: Table) -> Size {
+        Size {
+            w: table.columns.iter().map(|x| x.width).sum(),
+            h: TABLE_HEADER_HEIGHT + table.rows.len() as f32 * TABLE_ROW_HEIGHT,
+        }
+    }
+
+    fn table_hit_test(
+        &mut self,
+        control: Control,
+        table: Table,
+        scroll: Point,
+        point: Point,
+    ) -> TableHit {
+        let offset = self.table_get_offset(control, table, scroll);
+
+        if point.y < TABLE_HEADER_HEIGHT {
+            let mut x = -offset.x;
+            for (i, column) in table.columns.iter().enumerate() {
+                x += column.width;
+
+                if (point.x - x).abs() <= TABLE_DIVIDER_GRAB_WIDTH {
+                    return TableHit::Divider(i);
+                }
+                if point.x < x {
+                    return TableHit::Header(i);
+                }
+            }
+            return TableHit::None;
+        }
+
+        if table.rows.is_empty() {
+            return TableHit::None;
+        }
+
+        let index =
+            ((point.y - TABLE_HEADER_HEIGHT + offset.y) / TABLE_ROW_HEIGHT).max(0.0) as usize;
+
+        TableHit::Row(index.min(table.rows.len() - 1))
+    }
+
+    fn tree_view(
+        &mut self,
+        control: Control,
+        tree_view: TreeView,
+        rows: &[TreeRow],
+        row_count: usize,
+        scroll: Point,
+    ) {
+        let visual_state = self.get_visual_state(control);
+        let back_color = *self.listbox_back_colors.get(&visual_state).unwrap();
+        let border_color = *self.listbox_border_colors.get(&visual_state).unwrap();
+        let guide_color = *self.tree_view_guide_colors.get(&visual_state).unwrap();
+
+        self.quad(control.rect, back_color, border_color);
+
+        let offset = self.tree_view_get_offset(control, row_count, scroll);
+
+        for row in rows {
+            let mut item_visual_state = Normal;
+            if !control.enabled {
+                item_visual_state = Disabled;
+            }
+            if tree_view.selected.is_some_and(|x| x == row.node) {
+                item_visual_state = Active;
+            }
+            let item_back_color = *self
+                .listbox_item_back_colors
+                .get(&item_visual_state)
+                .unwrap();
+            let item_text_color = *self
+                .listbox_item_text_colors
+                .get(&item_visual_state)
+                .unwrap();
+            let expander_color = *self
+                .tree_view_expander_colors
+                .get(if control.enabled { &Normal } else { &Disabled })
+                .unwrap();
+
+            let row_rect = Rect::new(
+                control.rect.x,
+                control.rect.y + TREE_VIEW_ROW_HEIGHT * row.index as f32 - offset,
+                control.rect.w,
+                TREE_VIEW_ROW_HEIGHT,
+            )
+            .inflate(-1.0);
+
+            // Indentation guides run through the middle of every ancestor's expander column
+            for depth in 0..row.depth {
+                let x = control.rect.x + TREE_VIEW_INDENT * depth as f32 + TREE_VIEW_INDENT / 2.0;
+                self.line(
+                    Point { x, y: row_rect.y },
+                    Point {
+                        x,
+                        y: row_rect.bottom() + 1.0,
+                    },
+                    guide_color,
+                );
+            }
+
+            let label_x = control.rect.x + TREE_VIEW_INDENT * (row.depth + 1) as f32;
+            let label_rect = Rect::new(label_x, row_rect.y, row_rect.right() - label_x, row_rect.h);
+            self.quad(label_rect, item_back_color, item_back_color);
+
+            if row.has_children {
+                let expander_rect = Rect::new(
+                    control.rect.x
+                        + TREE_VIEW_INDENT * row.depth as f32
+                        + ((TREE_VIEW_INDENT - TREE_VIEW_EXPANDER_SIZE) / 2.0).floor(),
+                    row_rect.y + ((row_rect.h - TREE_VIEW_EXPANDER_SIZE) / 2.0).floor(),
+                    TREE_VIEW_EXPANDER_SIZE,
+                    TREE_VIEW_EXPANDER_SIZE,
+                );
+                self.tree_view_expander(expander_rect, row.expanded, expander_color);
+            }
+
+            let text_rect = label_rect.inflate(-LISTBOX_ITEM_PADDING);
+            let text = self.ellipsize(&row.label, text_rect.w);
+            self.draw_text(
+                &text,
+                text_rect,
+                item_text_color,
+                Alignment::Start,
+                Alignment::Center,
+            );
+        }
+
+        self.border(control.rect, border_color);
+    }
+
+    fn tree_view_get_content_size(&self, control: Control, row_count: usize) -> Size {
+        // Labels are ellipsized instead of scrolling horizontally, which spares us from measuring every row
+        Size {
+            w: control.rect.w,
+            h: row_count as f32 * TREE_VIEW_ROW_HEIGHT,
+        }
+    }
+
+    fn tree_view_get_visible_range(
+        &self,
+        control: Control,
+        row_count: usize,
+        scroll: Point,
+    ) -> (usize, usize) {
+        Self::get_visible_range(
+            row_count,
+            TREE_VIEW_ROW_HEIGHT,
+            control.rect.h,
+            self.tree_view_get_offset(control, row_count, scroll),
+        )
+    }
+
+    fn tree_view_hit_test(
+        &mut self,
+        control: Control,
+        rows: &[TreeRow],
+        row_count: usize,
+        scroll: Point,
+        point: Point,
+    ) -> TreeHit {
+        let offset = self.tree_view_get_offset(control, row_count, scroll);
+        let index = ((point.y + offset) / TREE_VIEW_ROW_HEIGHT).max(0.0) as usize;
+
+        let Some(row) = rows.iter().find(|x| x.index == index) else {
+            return TreeHit::None;
+        };
+
+        let expander_x = TREE_VIEW_INDENT * row.depth as f32;
+        if row.has_children && point.x >= expander_x && point.x < expander_x + TREE_VIEW_INDENT {
+            return TreeHit::Expander(index);
+        }
+
+        TreeHit::Row(index)
+    }
+
+    fn textbox(
+        &mut self,
+        control: Control,
+        textbox: Textbox,
+        scroll: Point,
+        caret: Option<usize>,
+        selection: Option<(usize, usize)>,
+    ) {
+        // Textboxes look active for as long as they're being typed into
+        let mut visual_state = self.get_visual_state(control);
+        if caret.is_some() && visual_state != Disabled {
+            visual_state = Active;
+        }
         let back_color = self.textbox_back_colors.get(&visual_state).unwrap().clone();
         let text_color = self.textbox_text_colors.get(&visual_state).unwrap().clone();
         let border_color = self
@@ -554,6 +1304,30 @@ impl<'a> Styler for StandardStyler<'a> {
 
         let content_rect = control.rect.inflate(-TEXTBOX_PADDING);
 
+        // The selection goes below the text, line by line from its start to its end
+        if let Some((start, end)) = selection.filter(|x| x.0 != x.1) {
+            let first = self.textbox_caret_rect(control, textbox, scroll, start.min(end));
+            let last = self.textbox_caret_rect(control, textbox, scroll, start.max(end));
+            let mut y = first.y;
+            while y <= last.y {
+                let left = if y == first.y {
+                    first.x
+                } else {
+                    content_rect.x
+                };
+                let right = if y == last.y {
+                    last.x
+                } else {
+                    content_rect.right()
+                };
+                self.fill(
+                    Rect::new(left, y, right - left, LINE_HEIGHT),
+                    self.textbox_selection_color,
+                );
+                y += LINE_HEIGHT;
+            }
+        }
+
         self.draw_text(
             textbox.text,
             content_rect,
@@ -562,26 +1336,30 @@ impl<'a> Styler for StandardStyler<'a> {
             Alignment::Center,
         );
 
-        // Now we draw the caret overlay + selection
-        if let Some(control_state) = self.persistent_state.control_state.get(&control.uid) {
-            let caret_position = self
-                .position_in_multiline_string(textbox.text, control_state.textbox_caret)
-                .add(content_rect.top_left());
-
-            self.canvas.set_draw_color(Color::BLACK);
-            self.canvas.draw_line(
-                caret_position.to_sdl(),
-                caret_position
-                    .add(Point {
-                        x: 0.0,
-                        y: LINE_HEIGHT,
-                    })
-                    .to_sdl(),
+        if let Some(caret) = caret.filter(|x| self.animations.caret_visible(control.uid, *x)) {
+            let caret_rect = self.textbox_caret_rect(control, textbox, scroll, caret);
+            self.line(
+                caret_rect.top_left(),
+                caret_rect.bottom_left(),
+                Color::BLACK,
             );
         }
     }
 
-    fn textbox_get_content_size(&self, control: Control, textbox: Textbox) -> Point {
+    fn textbox_caret_rect(
+        &mut self,
+        control: Control,
+        textbox: Textbox,
+        _scroll: Point,
+        index: usize,
+    ) -> Rect {
+        let content_rect = control.rect.inflate(-TEXTBOX_PADDING);
+        let position =
+            self.position_in_multiline_string(textbox.text, index) + content_rect.top_left();
+        Rect::new(position.x, position.y, 1.0, LINE_HEIGHT)
+    }
+
+    fn textbox_get_content_size(&self, control: Control, textbox: Textbox) -> Size {
         Default::default()
     }
 
@@ -594,7 +1372,7 @@ impl<'a> Styler for StandardStyler<'a> {
     ) -> Option<usize> {
         return Some(self.index_in_string(
             textbox.text,
-            point.sub(control.rect.inflate(-TEXTBOX_PADDING).top_left()),
+            point - control.rect.inflate(-TEXTBOX_PADDING).top_left(),
         ));
     }
 }
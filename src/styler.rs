@@ -1,12 +1,26 @@
-use crate::control::Textbox;
+use crate::control::{
+    Label, Splitter, Table, TableHit, Textbox, TreeHit, TreeRow, TreeView, Window,
+};
+use crate::debug::{DebugOverlay, DrawStats};
+use crate::geo::Size;
 use crate::*;
 
 pub trait Styler {
     fn begin(&mut self, persistent_state: PersistentState);
+    // Sets the factor between logical units and physical pixels
+    fn set_scale(&mut self, scale: f32);
+    // Restricts all following drawing to the specified rect, or lifts the restriction if none is specified
+    fn set_clip(&mut self, rect: Option<Rect>);
+    // Sends all following drawing to the layer, where higher layers end up on top of lower ones regardless of drawing order
+    // Everything outside of windows is drawn to layer 0
+    fn set_layer(&mut self, layer: usize);
+    // Marks the control as having keyboard focus, after it was drawn
+    fn focus_ring(&mut self, control: Control);
+    fn label(&mut self, control: Control, label: Label);
     fn button(&mut self, control: Control, button: Button);
     fn scrollbar(&mut self, control: Control, scrollbar: Scrollbar);
     fn listbox(&mut self, control: Control, listbox: Listbox, scroll: Point);
-    fn listbox_get_content_size(&self, control: Control, listbox: Listbox) -> Point;
+    fn listbox_get_content_size(&self, control: Control, listbox: Listbox) -> Size;
     fn listbox_index_at_point(
         &mut self,
         control: Control,
@@ -14,8 +28,16 @@ pub trait Styler {
         scroll: Point,
         point: Point,
     ) -> Option<usize>;
-    fn textbox(&mut self, control: Control, textbox: Textbox, scroll: Point);
-    fn textbox_get_content_size(&self, control: Control, textbox: Textbox) -> Point;
+    // The caret is only passed while the textbox is focused, and the selection is a range of byte offsets into the text
+    fn textbox(
+        &mut self,
+        control: Control,
+        textbox: Textbox,
+        scroll: Point,
+        caret: Option<usize>,
+        selection: Option<(usize, usize)>,
+    );
+    fn textbox_get_content_size(&self, control: Control, textbox: Textbox) -> Size;
     fn textbox_index_at_point(
         &mut self,
         control: Control,
@@ -23,6 +45,61 @@ pub trait Styler {
         scroll: Point,
         point: Point,
     ) -> Option<usize>;
+    // Gets where the caret is drawn while it's at the index, which is a byte offset into the text
+    fn textbox_caret_rect(
+        &mut self,
+        control: Control,
+        textbox: Textbox,
+        scroll: Point,
+        index: usize,
+    ) -> Rect;
+    fn table(&mut self, control: Control, table: Table, scroll: Point);
+    fn table_get_content_size(&self, control: Control, table: Table) -> Size;
+    fn table_hit_test(
+        &mut self,
+        control: Control,
+        table: Table,
+        scroll: Point,
+        point: Point,
+    ) -> TableHit;
+    fn tree_view(
+        &mut self,
+        control: Control,
+        tree_view: TreeView,
+        rows: &[TreeRow],
+        row_count: usize,
+        scroll: Point,
+    );
+    fn tree_view_get_content_size(&self, control: Control, row_count: usize) -> Size;
+    fn tree_view_get_visible_range(
+        &self,
+        control: Control,
+        row_count: usize,
+        scroll: Point,
+    ) -> (usize, usize);
+    fn tree_view_hit_test(
+        &mut self,
+        control: Control,
+        rows: &[TreeRow],
+        row_count: usize,
+        scroll: Point,
+        point: Point,
+    ) -> TreeHit;
+
+    // Draws the divider of a splitter, whose rect is the control's
+    fn splitter(&mut self, control: Control, splitter: Splitter);
+
+    // Gets how much was drawn since begin
+    fn draw_stats(&self) -> DrawStats;
+    // Draws the debug overlay on top of everything, right before end
+    fn debug_overlay(&mut self, overlay: &DebugOverlay);
+
+    // Draws the frame and title bar of a window, before its buttons and content
+    fn window(&mut self, control: Control, window: Window, title_bar: Rect);
+    // Dims everything drawn below a modal window, on the layer of the modal
+    fn modal_overlay(&mut self, rect: Rect);
 
     fn end(&mut self);
+    // Gets the time of the input clock at which the styler has to draw again even without new input, e.g. to continue animations
+    fn repaint_at(&self) -> Option<f64>;
 }
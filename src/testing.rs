@@ -0,0 +1,568 @@
+use crate::control::{
+    Button, ButtonMode, Control, Label, Listbox, Scrollbar, Splitter, Table, TableHit, TableSort,
+    Textbox, TreeHit, TreeRow, TreeView, Window,
+};
+use crate::debug::{DebugOverlay, DrawStats};
+use crate::geo::{Point, Rect, Size};
+use crate::input::{Input, Key, Modifiers, MouseButton};
+use crate::output::FrameOutput;
+use crate::styler::Styler;
+use crate::{PersistentState, Ugui};
+
+// Metrics the RecordingStyler lays out content with, which are simple enough to compute expected positions by hand
+pub const ITEM_HEIGHT: f32 = 20.0;
+pub const CHAR_WIDTH: f32 = 8.0;
+pub const LINE_HEIGHT: f32 = 16.0;
+pub const TABLE_HEADER_HEIGHT: f32 = 20.0;
+pub const TABLE_DIVIDER_GRAB_WIDTH: f32 = 4.0;
+pub const TREE_VIEW_INDENT: f32 = 16.0;
+
+// Time between two frames of a script
+const FRAME_TIME: f64 = 1.0 / 60.0;
+// How many frames a drag moves the mouse over
+const DRAG_STEPS: usize = 4;
+
+// A call made to the RecordingStyler, along with the parameters it was made with
+#[derive(Clone, Debug, PartialEq)]
+pub enum StylerCall {
+    Begin,
+    SetScale(f32),
+    SetClip(Option<Rect>),
+    SetLayer(usize),
+    FocusRing(Control),
+    Label {
+        control: Control,
+        text: String,
+    },
+    Button {
+        control: Control,
+        text: String,
+        mode: ButtonMode,
+    },
+    Scrollbar {
+        control: Control,
+        value: f32,
+        ratio: f32,
+    },
+    Listbox {
+        control: Control,
+        items: Vec<String>,
+        index: Option<usize>,
+        scroll: Point,
+    },
+    Textbox {
+        control: Control,
+        text: String,
+        scroll: Point,
+        caret: Option<usize>,
+        selection: Option<(usize, usize)>,
+    },
+    Table {
+        control: Control,
+        column_widths: Vec<f32>,
+        row_count: usize,
+        index: Option<usize>,
+        sort: Option<TableSort>,
+        scroll: Point,
+    },
+    TreeView {
+        control: Control,
+        selected: Option<u64>,
+        rows: Vec<TreeRow>,
+        row_count: usize,
+        scroll: Point,
+    },
+    Window {
+        control: Control,
+        title: String,
+        collapsed: bool,
+        title_bar: Rect,
+    },
+    ModalOverlay(Rect),
+    Splitter {
+        control: Control,
+        splitter: Splitter,
+    },
+    DebugOverlay(DebugOverlay),
+    End,
+}
+
+impl StylerCall {
+    // Gets the control a drawing call was made for
+    pub fn control(&self) -> Option<Control> {
+        match self {
+            StylerCall::FocusRing(control)
+            | StylerCall::Label { control, .. }
+            | StylerCall::Button { control, .. }
+            | StylerCall::Scrollbar { control, .. }
+            | StylerCall::Listbox { control, .. }
+            | StylerCall::Textbox { control, .. }
+            | StylerCall::Table { control, .. }
+            | StylerCall::TreeView { control, .. }
+            | StylerCall::Window { control, .. }
+            | StylerCall::Splitter { control, .. } => Some(*control),
+            _ => None,
+        }
+    }
+}
+
+// A styler which draws nothing and logs all calls instead, so controls can be tested without any rendering backend
+#[derive(Default)]
+pub struct RecordingStyler {
+    pub calls: Vec<StylerCall>,
+    pub persistent_state: PersistentState,
+}
+
+impl RecordingStyler {
+    pub fn new() -> RecordingStyler {
+        Default::default()
+    }
+
+    // Gets all drawing calls made for the control with the uid
+    pub fn calls_for(&self, uid: i64) -> Vec<&StylerCall> {
+        self.calls
+            .iter()
+            .filter(|x| x.control().is_some_and(|x| x.uid == uid))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.calls.clear();
+    }
+}
+
+fn visible_range(
+    item_count: usize,
+    item_height: f32,
+    viewport_height: f32,
+    offset: f32,
+) -> (usize, usize) {
+    let index_begin = (offset / item_height) as usize;
+    let index_end = ((viewport_height + offset) / item_height) as usize + 1;
+    (index_begin.min(item_count), index_end.min(item_count))
+}
+
+// Gets how far the content is scrolled in, given the scroll values between 0 and 1
+fn scroll_offset(control: Control, content_size: Size, scroll: Point) -> Point {
+    Point {
+        x: ((content_size.w - control.rect.w) * scroll.x).max(0.0),
+        y: ((content_size.h - control.rect.h) * scroll.y).max(0.0),
+    }
+}
+
+impl Styler for RecordingStyler {
+    fn begin(&mut self, persistent_state: PersistentState) {
+        self.persistent_state = persistent_state;
+        self.calls.push(StylerCall::Begin);
+    }
+
+    fn set_scale(&mut self, scale: f32) {
+        self.calls.push(StylerCall::SetScale(scale));
+    }
+
+    fn set_clip(&mut self, rect: Option<Rect>) {
+        self.calls.push(StylerCall::SetClip(rect));
+    }
+
+    fn set_layer(&mut self, layer: usize) {
+        self.calls.push(StylerCall::SetLayer(layer));
+    }
+
+    fn focus_ring(&mut self, control: Control) {
+        self.calls.push(StylerCall::FocusRing(control));
+    }
+
+    fn label(&mut self, control: Control, label: Label) {
+        self.calls.push(StylerCall::Label {
+            control,
+            text: label.text.to_string(),
+        });
+    }
+
+    fn button(&mut self, control: Control, button: Button) {
+        self.calls.push(StylerCall::Button {
+            control,
+            text: button.text.to_string(),
+            mode: button.mode,
+        });
+    }
+
+    fn scrollbar(&mut self, control: Control, scrollbar: Scrollbar) {
+        self.calls.push(StylerCall::Scrollbar {
+            control,
+            value: scrollbar.value,
+            ratio: scrollbar.ratio,
+        });
+    }
+
+    fn listbox(&mut self, control: Control, listbox: Listbox, scroll: Point) {
+        self.calls.push(StylerCall::Listbox {
+            control,
+            items: listbox.items.iter().map(|x| x.to_string()).collect(),
+            index: listbox.index,
+            scroll,
+        });
+    }
+
+    fn listbox_get_content_size(&self, control: Control, listbox: Listbox) -> Size {
+        let longest_item = listbox.items.iter().map(|x| x.len()).max().unwrap_or(0);
+        Size {
+            w: (longest_item as f32 * CHAR_WIDTH).max(control.rect.w),
+            h: listbox.items.len() as f32 * ITEM_HEIGHT,
+        }
+    }
+
+    fn listbox_index_at_point(
+        &mut self,
+        control: Control,
+        listbox: Listbox,
+        scroll: Point,
+        point: Point,
+    ) -> Option<usize> {
+        if listbox.items.is_empty() {
+            return listbox.index;
+        }
+        let content_size = self.listbox_get_content_size(control, listbox);
+        let offset = scroll_offset(control, content_size, scroll);
+        let index = ((point.y + offset.y) / ITEM_HEIGHT).max(0.0) as usize;
+        Some(index.min(listbox.items.len() - 1))
+    }
+
+    fn textbox(
+        &mut self,
+        control: Control,
+        textbox: Textbox,
+        scroll: Point,
+        caret: Option<usize>,
+        selection: Option<(usize, usize)>,
+    ) {
+        self.calls.push(StylerCall::Textbox {
+            control,
+            text: textbox.text.clone(),
+            scroll,
+            caret,
+            selection,
+        });
+    }
+
+    fn textbox_get_content_size(&self, _control: Control, _textbox: Textbox) -> Size {
+        Size::default()
+    }
+
+    // Text is laid out in monospace with no padding, and the index is the byte offset of the closest char boundary
+    fn textbox_index_at_point(
+        &mut self,
+        control: Control,
+        textbox: Textbox,
+        _scroll: Point,
+        point: Point,
+    ) -> Option<usize> {
+        let relative = point - control.rect.top_left();
+        let lines: Vec<&str> = textbox.text.split('\n').collect();
+        let line_index = ((relative.y / LINE_HEIGHT).max(0.0) as usize).min(lines.len() - 1);
+        let column = (relative.x / CHAR_WIDTH).round().max(0.0) as usize;
+
+        let line_start: usize = lines[..line_index].iter().map(|x| x.len() + 1).sum();
+        let line = lines[line_index];
+        let offset = line
+            .char_indices()
+            .map(|x| x.0)
+            .chain(std::iter::once(line.len()))
+            .nth(column)
+            .unwrap_or(line.len());
+        Some(line_start + offset)
+    }
+
+    fn textbox_caret_rect(
+        &mut self,
+        control: Control,
+        textbox: Textbox,
+        _scroll: Point,
+        index: usize,
+    ) -> Rect {
+        let before = &textbox.text[..index.min(textbox.text.len())];
+        let line = before.matches('\n').count();
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count();
+        Rect::new(
+            control.rect.x + column as f32 * CHAR_WIDTH,
+            control.rect.y + line as f32 * LINE_HEIGHT,
+            1.0,
+            LINE_HEIGHT,
+        )
+    }
+
+    fn table(&mut self, control: Control, table: Table, scroll: Point) {
+        self.calls.push(StylerCall::Table {
+            control,
+            column_widths: table.columns.iter().map(|x| x.width).collect(),
+            row_count: table.rows.len(),
+            index: table.index,
+            sort: table.sort,
+            scroll,
+        });
+    }
+
+    fn table_get_content_size(&self, _control: Control, table: Table) -> Size {
+        Size {
+            w: table.columns.iter().map(|x| x.width).sum(),
+            h: TABLE_HEADER_HEIGHT + table.rows.len() as f32 * ITEM_HEIGHT,
+        }
+    }
+
+    fn table_hit_test(
+        &mut self,
+        control: Control,
+        table: Table,
+        scroll: Point,
+        point: Point,
+    ) -> TableHit {
+        let content_size = self.table_get_content_size(control, table);
+        let offset = scroll_offset(control, content_size, scroll);
+
+        if point.y < TABLE_HEADER_HEIGHT {
+            let mut x = -offset.x;
+            for (i, column) in table.columns.iter().enumerate() {
+                x += column.width;
+                if (point.x - x).abs() <= TABLE_DIVIDER_GRAB_WIDTH {
+                    return TableHit::Divider(i);
+                }
+                if point.x < x {
+                    return TableHit::Header(i);
+                }
+            }
+            return TableHit::None;
+        }
+
+        if table.rows.is_empty() {
+            return TableHit::None;
+        }
+        let index = ((point.y - TABLE_HEADER_HEIGHT + offset.y) / ITEM_HEIGHT).max(0.0) as usize;
+        TableHit::Row(index.min(table.rows.len() - 1))
+    }
+
+    fn tree_view(
+        &mut self,
+        control: Control,
+        tree_view: TreeView,
+        rows: &[TreeRow],
+        row_count: usize,
+        scroll: Point,
+    ) {
+        self.calls.push(StylerCall::TreeView {
+            control,
+            selected: tree_view.selected,
+            rows: rows.to_vec(),
+            row_count,
+            scroll,
+        });
+    }
+
+    fn tree_view_get_content_size(&self, control: Control, row_count: usize) -> Size {
+        Size {
+            w: control.rect.w,
+            h: row_count as f32 * ITEM_HEIGHT,
+        }
+    }
+
+    fn tree_view_get_visible_range(
+        &self,
+        control: Control,
+        row_count: usize,
+        scroll: Point,
+    ) -> (usize, usize) {
+        let content_size = self.tree_view_get_content_size(control, row_count);
+        let offset = scroll_offset(control, content_size, scroll);
+        visible_range(row_count, ITEM_HEIGHT, control.rect.h, offset.y)
+    }
+
+    fn tree_view_hit_test(
+        &mut self,
+        control: Control,
+        rows: &[TreeRow],
+        row_count: usize,
+        scroll: Point,
+        point: Point,
+    ) -> TreeHit {
+        let content_size = self.tree_view_get_content_size(control, row_count);
+        let offset = scroll_offset(control, content_size, scroll);
+        let index = ((point.y + offset.y) / ITEM_HEIGHT).max(0.0) as usize;
+
+        let Some(row) = rows.iter().find(|x| x.index == index) else {
+            return TreeHit::None;
+        };
+
+        let expander_x = TREE_VIEW_INDENT * row.depth as f32;
+        if row.has_children && point.x >= expander_x && point.x < expander_x + TREE_VIEW_INDENT {
+            return TreeHit::Expander(index);
+        }
+        TreeHit::Row(index)
+    }
+
+    fn window(&mut self, control: Control, window: Window, title_bar: Rect) {
+        self.calls.push(StylerCall::Window {
+            control,
+            title: window.title.to_string(),
+            collapsed: window.collapsed,
+            title_bar,
+        });
+    }
+
+    fn splitter(&mut self, control: Control, splitter: Splitter) {
+        self.calls.push(StylerCall::Splitter { control, splitter });
+    }
+
+    // Nothing is animated, since the calls are logged with their final values
+    fn repaint_at(&self) -> Option<f64> {
+        None
+    }
+
+    fn modal_overlay(&mut self, rect: Rect) {
+        self.calls.push(StylerCall::ModalOverlay(rect));
+    }
+
+    // Nothing is actually drawn, so each logged drawing call counts as one
+    fn draw_stats(&self) -> DrawStats {
+        let calls_since_begin = self
+            .calls
+            .iter()
+            .rev()
+            .take_while(|x| **x != StylerCall::Begin)
+            .filter(|x| x.control().is_some());
+        DrawStats {
+            draw_calls: calls_since_begin.count() as u32,
+            text_renders: 0,
+        }
+    }
+
+    fn debug_overlay(&mut self, overlay: &DebugOverlay) {
+        self.calls.push(StylerCall::DebugOverlay(overlay.clone()));
+    }
+
+    fn end(&mut self) {
+        self.calls.push(StylerCall::End);
+    }
+}
+
+// Builds the inputs for a sequence of frames, e.g. Script::new().move_to(10.0, 10.0).press().release()
+// Every step produces one frame, and the time advances by a sixtieth of a second per frame
+#[derive(Clone, Default)]
+pub struct Script {
+    inputs: Vec<Input>,
+    // The input of the next frame, whose mouse position, buttons and modifiers carry over to the following ones
+    current: Input,
+}
+
+impl Script {
+    pub fn new() -> Script {
+        Default::default()
+    }
+
+    pub fn inputs(&self) -> &[Input] {
+        &self.inputs
+    }
+
+    // Emits the current input as a frame and resets everything which is only reported once
+    fn frame(mut self) -> Script {
+        self.inputs.push(self.current.clone());
+        self.current.time += FRAME_TIME;
+        self.current.mouse_wheel = 0;
+        self.current.pressed_keys.clear();
+        self.current.text.clear();
+        self
+    }
+
+    fn set_button(mut self, button: MouseButton, down: bool) -> Script {
+        match button {
+            MouseButton::Primary => self.current.primary_down = down,
+            MouseButton::Secondary => self.current.secondary_down = down,
+            MouseButton::Middle => self.current.middle_down = down,
+        }
+        self.frame()
+    }
+
+    // Runs a frame without any new input
+    pub fn idle(self) -> Script {
+        self.frame()
+    }
+
+    // Lets time pass without running any frames
+    pub fn wait(mut self, seconds: f64) -> Script {
+        self.current.time += seconds;
+        self
+    }
+
+    pub fn move_to(mut self, x: f32, y: f32) -> Script {
+        self.current.mouse_position = Point { x, y };
+        self.frame()
+    }
+
+    pub fn press(self) -> Script {
+        self.set_button(MouseButton::Primary, true)
+    }
+
+    pub fn release(self) -> Script {
+        self.set_button(MouseButton::Primary, false)
+    }
+
+    pub fn press_button(self, button: MouseButton) -> Script {
+        self.set_button(button, true)
+    }
+
+    pub fn release_button(self, button: MouseButton) -> Script {
+        self.set_button(button, false)
+    }
+
+    // Moves to the point, then pushes down and releases the primary button
+    pub fn click(self, x: f32, y: f32) -> Script {
+        self.move_to(x, y).press().release()
+    }
+
+    // Pushes down the primary button at the current position, moves to the point over a few frames and releases it
+    pub fn drag_to(mut self, x: f32, y: f32) -> Script {
+        let start = self.current.mouse_position;
+        let end = Point { x, y };
+        self = self.press();
+        for i in 1..=DRAG_STEPS {
+            let position = start + (end - start) * (i as f32 / DRAG_STEPS as f32);
+            self = self.move_to(position.x, position.y);
+        }
+        self.release()
+    }
+
+    pub fn type_text(mut self, text: &str) -> Script {
+        self.current.text.push_str(text);
+        self.frame()
+    }
+
+    pub fn key(mut self, key: Key) -> Script {
+        self.current.pressed_keys.push(key);
+        self.frame()
+    }
+
+    pub fn wheel(mut self, amount: i8) -> Script {
+        self.current.mouse_wheel = amount;
+        self.frame()
+    }
+
+    // Sets the modifiers held down during all following frames
+    pub fn modifiers(mut self, modifiers: Modifiers) -> Script {
+        self.current.modifiers = modifiers;
+        self
+    }
+
+    // Runs all frames of the script, calling frame between begin and end to place the controls
+    // Returns the output of the last frame
+    pub fn run<T: Styler>(
+        &self,
+        ugui: &mut Ugui<T>,
+        mut frame: impl FnMut(&mut Ugui<T>),
+    ) -> FrameOutput {
+        let mut output = FrameOutput::default();
+        for input in &self.inputs {
+            ugui.begin(input.clone());
+            frame(ugui);
+            output = ugui.end();
+        }
+        output
+    }
+}
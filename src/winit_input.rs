@@ -0,0 +1,161 @@
+use crate::geo::Point;
+use crate::input::{Input, InputAccumulator, Key, Modifiers, MouseButton};
+use crate::output::{CursorIcon, FrameOutput};
+use std::time::Duration;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{
+    ElementState, Ime, MouseButton as WinitMouseButton, MouseScrollDelta, WindowEvent,
+};
+use winit::keyboard::{Key as WinitKey, NamedKey};
+use winit::window::{CursorIcon as WinitCursorIcon, Window};
+
+// How many pixels of smooth scrolling, e.g. from touchpads, make up one wheel notch
+const PIXELS_PER_WHEEL_NOTCH: f64 = 20.0;
+
+// Builds the Input for each frame out of the winit window events received since the last one
+pub struct InputCollector {
+    input: InputAccumulator,
+    // Whether text arrives through the IME rather than with key events
+    ime_allowed: bool,
+    // The cursor which was set on the window last
+    cursor: CursorIcon,
+    // Smooth scrolling which didn't add up to a full notch yet
+    pixel_scroll: f64,
+}
+
+fn map_cursor(cursor: CursorIcon) -> WinitCursorIcon {
+    match cursor {
+        CursorIcon::Default => WinitCursorIcon::Default,
+        CursorIcon::Text => WinitCursorIcon::Text,
+        CursorIcon::ResizeHorizontal => WinitCursorIcon::EwResize,
+        CursorIcon::ResizeVertical => WinitCursorIcon::NsResize,
+        CursorIcon::ResizeNwSe => WinitCursorIcon::NwseResize,
+        CursorIcon::ResizeNeSw => WinitCursorIcon::NeswResize,
+    }
+}
+
+fn map_key(key: &WinitKey) -> Option<Key> {
+    match key {
+        WinitKey::Named(NamedKey::ArrowUp) => Some(Key::Up),
+        WinitKey::Named(NamedKey::ArrowDown) => Some(Key::Down),
+        WinitKey::Named(NamedKey::ArrowLeft) => Some(Key::Left),
+        WinitKey::Named(NamedKey::ArrowRight) => Some(Key::Right),
+        WinitKey::Named(NamedKey::Home) => Some(Key::Home),
+        WinitKey::Named(NamedKey::End) => Some(Key::End),
+        WinitKey::Named(NamedKey::PageUp) => Some(Key::PageUp),
+        WinitKey::Named(NamedKey::PageDown) => Some(Key::PageDown),
+        WinitKey::Named(NamedKey::Enter) => Some(Key::Enter),
+        WinitKey::Named(NamedKey::Space) => Some(Key::Space),
+        WinitKey::Named(NamedKey::Tab) => Some(Key::Tab),
+        WinitKey::Named(NamedKey::Backspace) => Some(Key::Backspace),
+        WinitKey::Named(NamedKey::Delete) => Some(Key::Delete),
+        WinitKey::Named(NamedKey::Escape) => Some(Key::Escape),
+        _ => None,
+    }
+}
+
+impl Default for InputCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputCollector {
+    pub fn new() -> InputCollector {
+        InputCollector {
+            input: InputAccumulator::new(),
+            ime_allowed: false,
+            cursor: CursorIcon::Default,
+            pixel_scroll: 0.0,
+        }
+    }
+
+    pub fn process_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.input.mouse_moved(Point {
+                    x: position.x as f32,
+                    y: position.y as f32,
+                });
+            }
+            WindowEvent::CursorLeft { .. } => self.input.mouse_left(),
+            WindowEvent::MouseInput { state, button, .. } => {
+                let button = match button {
+                    WinitMouseButton::Left => MouseButton::Primary,
+                    WinitMouseButton::Right => MouseButton::Secondary,
+                    WinitMouseButton::Middle => MouseButton::Middle,
+                    _ => return,
+                };
+                self.input
+                    .button_changed(button, *state == ElementState::Pressed);
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let notches = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y as f64,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        self.pixel_scroll += position.y / PIXELS_PER_WHEEL_NOTCH;
+                        let notches = self.pixel_scroll.trunc();
+                        self.pixel_scroll -= notches;
+                        notches
+                    }
+                };
+                self.input.wheel_turned(notches);
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                if event.state != ElementState::Pressed {
+                    return;
+                }
+                if let Some(key) = map_key(&event.logical_key) {
+                    self.input.key_pressed(key);
+                }
+                if let Some(text) = event.text.as_ref().filter(|_| !self.ime_allowed) {
+                    self.input.text_typed(text);
+                }
+            }
+            WindowEvent::Ime(Ime::Commit(text)) => {
+                self.input.text_typed(text);
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                self.input.modifiers_changed(Modifiers {
+                    shift: state.shift_key(),
+                    ctrl: state.control_key(),
+                    alt: state.alt_key(),
+                    super_key: state.super_key(),
+                });
+            }
+            WindowEvent::Focused(false) => self.input.focus_lost(),
+            _ => {}
+        }
+    }
+
+    // Gets how long it is until the time of the input clock, e.g. to wait for FrameOutput::repaint_at
+    pub fn time_until(&self, time: f64) -> Duration {
+        self.input.time_until(time)
+    }
+
+    // Gets the input for the next call to Ugui::begin, consuming everything which is only reported once
+    pub fn take_input(&mut self) -> Input {
+        self.input.take_input()
+    }
+
+    // Allows or disallows IME input on the window, which also controls on-screen keyboards, and sets the cursor
+    // Should be called with the output of each frame
+    pub fn apply_output(&mut self, window: &Window, output: &FrameOutput) {
+        if output.cursor != self.cursor {
+            self.cursor = output.cursor;
+            window.set_cursor(map_cursor(output.cursor));
+        }
+        if let Some(rect) = output.ime_rect {
+            window.set_ime_cursor_area(
+                PhysicalPosition::new(rect.x, rect.y),
+                PhysicalSize::new(rect.w, rect.h),
+            );
+        }
+        if output.text_input == self.ime_allowed {
+            return;
+        }
+        self.ime_allowed = output.text_input;
+        window.set_ime_allowed(output.text_input);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::painter::{Color, Painter, PixelRect};
+use crate::software_painter::SoftwarePainter;
+use softbuffer::{Context, Surface};
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use winit::window::Window;
+
+// Draws into a software framebuffer which is presented to a winit window through softbuffer
+pub struct WinitPainter {
+    painter: SoftwarePainter,
+    window: Rc<Window>,
+    surface: Surface<Rc<Window>, Rc<Window>>,
+}
+
+impl WinitPainter {
+    pub fn new(window: Rc<Window>, font_data: &[u8]) -> Result<Self, String> {
+        let context = Context::new(window.clone()).map_err(|e| e.to_string())?;
+        let surface = Surface::new(&context, window.clone()).map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            painter: SoftwarePainter::new(font_data)?,
+            window,
+            surface,
+        })
+    }
+}
+
+impl Painter for WinitPainter {
+    fn clear(&mut self, color: Color) {
+        // The framebuffer follows the window's size, which is only picked up at the start of a frame
+        let size = self.window.inner_size();
+        if size.width != self.painter.width() || size.height != self.painter.height() {
+            self.painter.resize(size.width, size.height);
+        }
+        self.painter.clear(color);
+    }
+
+    fn fill_rect(&mut self, rect: PixelRect, color: Color) {
+        self.painter.fill_rect(rect, color);
+    }
+
+    fn set_clip(&mut self, rect: Option<PixelRect>) {
+        self.painter.set_clip(rect);
+    }
+
+    fn set_font_size(&mut self, size: u16) {
+        self.painter.set_font_size(size);
+    }
+
+    fn text_size(&self, text: &str) -> (u32, u32) {
+        self.painter.text_size(text)
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, color: Color) {
+        self.painter.draw_text(text, x, y, color);
+    }
+
+    fn present(&mut self) {
+        // Minimized windows have no area to present to
+        let (Some(width), Some(height)) = (
+            NonZeroU32::new(self.painter.width()),
+            NonZeroU32::new(self.painter.height()),
+        ) else {
+            return;
+        };
+
+        self.surface.resize(width, height).unwrap();
+        let mut buffer = self.surface.buffer_mut().unwrap();
+        buffer.copy_from_slice(self.painter.pixels());
+        buffer.present().unwrap();
+    }
+}
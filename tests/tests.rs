@@ -1,8 +1,1925 @@
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use ugui::animation::Animations;
+    use ugui::control::{
+        Button, ButtonMode, Control, Listbox, MessageBoxButtons, MessageBoxResult, Scrollbar,
+        SortDirection, Splitter, SplitterPane, SplitterPanes, Table, TableColumn, TableSort,
+        Textbox, TreeModel, TreeView,
+    };
+    use ugui::geo::{Insets, Point, Rect, Size};
+    use ugui::input::{Input, InputAccumulator, Key, Modifiers, MouseButton};
+    use ugui::output::CursorIcon;
+    use ugui::painter::Color;
+    use ugui::response::Response;
+    use ugui::testing::{RecordingStyler, Script, StylerCall, CHAR_WIDTH};
+    use ugui::Ugui;
+    #[cfg(feature = "software")]
+    use ugui::{software_painter::SoftwarePainter, standard_styler::StandardStyler};
 
     #[test]
     fn it_works() {
         assert_eq!(4, 4);
     }
+
+    #[test]
+    fn point_operators() {
+        let a = Point { x: 1.0, y: 2.0 };
+        let b = Point { x: 3.0, y: 5.0 };
+
+        assert_eq!(a + b, Point { x: 4.0, y: 7.0 });
+        assert_eq!(b - a, Point { x: 2.0, y: 3.0 });
+        assert_eq!(a * 2.0, Point { x: 2.0, y: 4.0 });
+        assert_eq!(-a, Point { x: -1.0, y: -2.0 });
+    }
+
+    #[test]
+    fn point_inside_edges() {
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let edge = Point { x: 0.0, y: 5.0 };
+
+        assert!(edge.inside_inclusive(rect));
+        assert!(!edge.inside_exclusive(rect));
+        assert!(!edge.inside(rect));
+    }
+
+    #[test]
+    fn rect_intersect_and_union() {
+        let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let b = Rect::new(5.0, 5.0, 10.0, 10.0);
+
+        assert_eq!(a.intersect(b), Rect::new(5.0, 5.0, 5.0, 5.0));
+        assert_eq!(a.union(b), Rect::new(0.0, 0.0, 15.0, 15.0));
+        assert_eq!(
+            a.intersect(Rect::new(20.0, 20.0, 5.0, 5.0)).size(),
+            Size::new(0.0, 0.0)
+        );
+        assert!(a.union(b).contains_rect(a));
+        assert!(!a.contains_rect(b));
+    }
+
+    #[test]
+    fn rect_layout_helpers() {
+        let mut rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+
+        assert_eq!(rect.cut_left(20.0), Rect::new(0.0, 0.0, 20.0, 50.0));
+        assert_eq!(rect.cut_bottom(10.0), Rect::new(20.0, 40.0, 80.0, 10.0));
+        assert_eq!(rect, Rect::new(20.0, 0.0, 80.0, 40.0));
+
+        let (top, bottom) = rect.split_y(15.0);
+        assert_eq!(top, Rect::new(20.0, 0.0, 80.0, 15.0));
+        assert_eq!(bottom, Rect::new(20.0, 15.0, 80.0, 25.0));
+
+        assert_eq!(
+            rect.shrink(Insets::new(1.0, 2.0, 3.0, 4.0)),
+            Rect::new(21.0, 2.0, 76.0, 34.0)
+        );
+        assert_eq!(rect.center(), Point { x: 60.0, y: 20.0 });
+        assert_eq!(
+            rect + Point { x: 5.0, y: 5.0 },
+            Rect::new(25.0, 5.0, 80.0, 40.0)
+        );
+    }
+
+    #[cfg(feature = "software")]
+    fn software_ugui() -> Ugui<StandardStyler<SoftwarePainter>> {
+        let font_data = std::fs::read("examples/fonts/segoe.ttf").unwrap();
+        let mut painter = SoftwarePainter::new(&font_data).unwrap();
+        painter.resize(100, 50);
+        Ugui::new(StandardStyler::new(painter))
+    }
+
+    // Runs a frame with a textbox containing "abc" and returns its text
+    #[cfg(feature = "software")]
+    fn textbox_frame(ugui: &mut Ugui<StandardStyler<SoftwarePainter>>, input: Input) -> String {
+        ugui.begin(input);
+        let text = ugui
+            .textbox(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 100.0, 50.0),
+                },
+                Textbox {
+                    text: &"abc".to_string(),
+                },
+            )
+            .value;
+        ugui.end();
+        text
+    }
+
+    // Focuses the textbox and types into it, which leaves the caret after the typed text
+    #[cfg(feature = "software")]
+    fn type_into_textbox(ugui: &mut Ugui<StandardStyler<SoftwarePainter>>, text: &str) -> String {
+        textbox_frame(
+            ugui,
+            Input {
+                pressed_keys: vec![Key::Tab],
+                ..Default::default()
+            },
+        );
+        textbox_frame(
+            ugui,
+            Input {
+                text: text.to_string(),
+                ..Default::default()
+            },
+        )
+    }
+
+    // Renders without any windowing system, so this runs headless
+    #[cfg(feature = "software")]
+    #[test]
+    fn software_painter_draws_button() {
+        use ugui::control::{Button, Control};
+        use ugui::input::Input;
+        use ugui::software_painter::SoftwarePainter;
+        use ugui::standard_styler::StandardStyler;
+        use ugui::Ugui;
+
+        let font_data = std::fs::read("examples/fonts/segoe.ttf").unwrap();
+        let mut painter = SoftwarePainter::new(&font_data).unwrap();
+        painter.resize(100, 50);
+
+        let mut ugui = Ugui::new(StandardStyler::new(painter));
+        ugui.begin(Input::default());
+        ugui.button(
+            Control {
+                uid: 0,
+                enabled: true,
+                rect: Rect::new(10.0, 10.0, 80.0, 30.0),
+            },
+            Button {
+                text: "",
+                ..Default::default()
+            },
+        );
+        ugui.end();
+
+        let painter = &ugui.styler.painter;
+        let pixel = |x: u32, y: u32| painter.pixels()[(y * painter.width() + x) as usize];
+        assert_eq!(pixel(0, 0), 0xFDFDFD);
+        assert_eq!(pixel(10, 10), 0xADADAD);
+        assert_eq!(pixel(50, 25), 0xE1E1E1);
+    }
+
+    #[cfg(all(feature = "serde", feature = "software"))]
+    #[test]
+    fn save_and_load_state() {
+        use ugui::control::{Control, Textbox};
+        use ugui::input::{Input, Key};
+        use ugui::software_painter::SoftwarePainter;
+        use ugui::standard_styler::StandardStyler;
+        use ugui::Ugui;
+
+        let font_data = std::fs::read("examples/fonts/segoe.ttf").unwrap();
+        let new_ugui = || {
+            let mut painter = SoftwarePainter::new(&font_data).unwrap();
+            painter.resize(100, 50);
+            Ugui::new(StandardStyler::new(painter))
+        };
+        let textbox = |ugui: &mut Ugui<_>, input: Input| {
+            ugui.begin(input);
+            let text = ugui
+                .textbox(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 100.0, 50.0),
+                    },
+                    Textbox {
+                        text: &"abc".to_string(),
+                    },
+                )
+                .value;
+            ugui.end();
+            text
+        };
+
+        // Focus the textbox and type into it, which moves the caret
+        let mut ugui = new_ugui();
+        textbox(
+            &mut ugui,
+            Input {
+                pressed_keys: vec![Key::Tab],
+                ..Default::default()
+            },
+        );
+        let text = textbox(
+            &mut ugui,
+            Input {
+                text: "xy".to_string(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(text, "xyabc");
+
+        let mut saved = vec![];
+        ugui.save_state(&mut saved).unwrap();
+        assert!(String::from_utf8_lossy(&saved).contains("\"textbox_caret\":2"));
+
+        let mut loaded_ugui = new_ugui();
+        loaded_ugui.load_state(saved.as_slice()).unwrap();
+        let mut saved_again = vec![];
+        loaded_ugui.save_state(&mut saved_again).unwrap();
+        assert_eq!(saved, saved_again);
+
+        let future_state = String::from_utf8(saved)
+            .unwrap()
+            .replace("\"version\":1", "\"version\":2");
+        assert!(loaded_ugui.load_state(future_state.as_bytes()).is_err());
+    }
+
+    // The caret position tells whether the textbox kept its state while it wasn't placed
+    #[cfg(feature = "software")]
+    #[test]
+    fn stale_state_is_evicted_unless_pinned() {
+        let skip_frames = |ugui: &mut Ugui<_>, count: usize| {
+            for _ in 0..count {
+                ugui.begin(Input::default());
+                ugui.end();
+            }
+        };
+
+        // Typing leaves the caret at 1, so the next text goes there unless the state was evicted
+        let typed_after_skipping = |frames: usize, pinned: bool| {
+            let mut ugui = software_ugui();
+            ugui.set_state_retention_frames(3);
+            if pinned {
+                ugui.pin_state(0);
+            }
+            type_into_textbox(&mut ugui, "x");
+            skip_frames(&mut ugui, frames);
+            type_into_textbox(&mut ugui, "y")
+        };
+
+        assert_eq!(typed_after_skipping(3, false), "aybc");
+        assert_eq!(typed_after_skipping(4, false), "yabc");
+        assert_eq!(typed_after_skipping(10, true), "aybc");
+    }
+
+    #[test]
+    fn pinned_state_keeps_sub_controls() {
+        let items = vec!["a"; 10];
+        let listbox = |ugui: &mut Ugui<RecordingStyler>| {
+            ugui.listbox(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 80.0, 60.0),
+                },
+                Listbox {
+                    items: &items,
+                    index: None,
+                },
+            );
+        };
+        let scrolled_after_skipping = |pinned: bool| {
+            let mut ugui = recording_ugui();
+            ugui.set_state_retention_frames(3);
+            if pinned {
+                ugui.pin_state(0);
+            }
+            Script::new()
+                .move_to(40.0, 30.0)
+                .wheel(-1)
+                .run(&mut ugui, listbox);
+            Script::new()
+                .idle()
+                .idle()
+                .idle()
+                .idle()
+                .run(&mut ugui, |_| {});
+            Script::new().idle().run(&mut ugui, listbox);
+            matches!(
+                ugui.styler.calls_for(0).last(),
+                Some(StylerCall::Listbox { scroll, .. }) if scroll.y > 0.0
+            )
+        };
+
+        // The scroll value belongs to the vertical scrollbar, which only the listbox was pinned for
+        assert!(!scrolled_after_skipping(false));
+        assert!(scrolled_after_skipping(true));
+    }
+
+    #[cfg(all(feature = "recording", feature = "software"))]
+    #[test]
+    fn recording_replays_identically() {
+        use ugui::recording::Recording;
+
+        let frame = |ugui: &mut Ugui<StandardStyler<SoftwarePainter>>, text: &mut String| {
+            ugui.button(
+                Control {
+                    uid: 1,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 50.0, 20.0),
+                },
+                Button {
+                    text: "",
+                    ..Default::default()
+                },
+            );
+            *text = ugui
+                .textbox(
+                    Control {
+                        uid: 2,
+                        enabled: true,
+                        rect: Rect::new(0.0, 25.0, 100.0, 25.0),
+                    },
+                    Textbox { text },
+                )
+                .value;
+        };
+        let inputs = vec![
+            Input {
+                mouse_position: Point { x: 10.0, y: 30.0 },
+                primary_down: true,
+                ..Default::default()
+            },
+            Input {
+                mouse_position: Point { x: 10.0, y: 30.0 },
+                text: "hello".to_string(),
+                time: 0.1,
+                ..Default::default()
+            },
+            Input {
+                pressed_keys: vec![Key::Backspace, Key::Tab],
+                time: 0.2,
+                ..Default::default()
+            },
+        ];
+
+        let mut ugui = software_ugui();
+        let mut text = String::new();
+        ugui.start_recording();
+        for input in inputs {
+            ugui.begin(input);
+            frame(&mut ugui, &mut text);
+            ugui.end();
+        }
+        let recording = ugui.stop_recording().unwrap();
+        assert_eq!(text, "hell");
+
+        let mut saved = vec![];
+        recording.save(&mut saved).unwrap();
+        let loaded = Recording::load(saved.as_slice()).unwrap();
+        assert_eq!(loaded, recording);
+
+        let mut text = String::new();
+        let result = loaded.replay(&mut software_ugui(), |ugui| frame(ugui, &mut text));
+        assert_eq!(result, Ok(()));
+        assert_eq!(text, "hell");
+
+        // Starting out with different text makes the textbox return different values
+        let mut text = "x".to_string();
+        let result = loaded.replay(&mut software_ugui(), |ugui| frame(ugui, &mut text));
+        assert!(result.is_err());
+    }
+
+    // Clicks land on items which are still gliding into place, so the recorded values depend on smooth scrolling
+    #[cfg(all(feature = "recording", feature = "software"))]
+    #[test]
+    fn recording_replays_scrolled_list_through_other_styler() {
+        fn frame<T: ugui::styler::Styler>(ugui: &mut Ugui<T>, index: &mut Option<usize>) {
+            let items: Vec<String> = (0..50).map(|x| x.to_string()).collect();
+            let items: Vec<&str> = items.iter().map(String::as_str).collect();
+            *index = ugui
+                .listbox(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 100.0, 50.0),
+                    },
+                    Listbox {
+                        items: &items,
+                        index: *index,
+                    },
+                )
+                .value;
+        }
+        let script = Script::new()
+            .move_to(20.0, 20.0)
+            .wheel(-1)
+            .press()
+            .release()
+            .wheel(-2)
+            .idle()
+            .click(20.0, 30.0);
+
+        let mut ugui = software_ugui();
+        let mut index = None;
+        ugui.start_recording();
+        script.run(&mut ugui, |ugui| frame(ugui, &mut index));
+        let recording = ugui.stop_recording().unwrap();
+        assert!(index.is_some_and(|x| x > 0));
+
+        let mut replayed_index = None;
+        let result = recording.replay(&mut recording_ugui(), |ugui| {
+            frame(ugui, &mut replayed_index)
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(replayed_index, index);
+
+        // Whatever the ugui went through before isn't part of the recording
+        let mut used_ugui = recording_ugui();
+        script.run(&mut used_ugui, |ugui| frame(ugui, &mut replayed_index));
+        assert!(recording
+            .replay(&mut used_ugui, |ugui| frame(ugui, &mut replayed_index))
+            .is_err());
+    }
+
+    #[cfg(feature = "recording")]
+    #[test]
+    fn recording_replays_viewport_and_access_actions() {
+        use ugui::access::AccessAction;
+
+        // The message box is centered in the viewport, and shown until its answer ends up in the textbox
+        let frame = |ugui: &mut Ugui<RecordingStyler>, text: &mut String| {
+            if text.is_empty() {
+                if let Some(result) =
+                    ugui.message_box(10, "Quit", "Save changes?", MessageBoxButtons::OkCancel)
+                {
+                    *text = format!("{result:?}");
+                }
+            }
+            *text = ugui
+                .textbox(
+                    Control {
+                        uid: 1,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 100.0, 20.0),
+                    },
+                    Textbox { text },
+                )
+                .value;
+        };
+
+        let mut ugui = recording_ugui();
+        ugui.set_viewport_size(Size::new(400.0, 300.0));
+        let mut text = String::new();
+        ugui.start_recording();
+        Script::new()
+            .idle()
+            .click(200.0, 195.0)
+            .idle()
+            .run(&mut ugui, |ugui| frame(ugui, &mut text));
+        assert_eq!(text, "Ok");
+        ugui.queue_access_action(1, AccessAction::SetValue("Ok!".to_string()));
+        Script::new()
+            .idle()
+            .run(&mut ugui, |ugui| frame(ugui, &mut text));
+        let recording = ugui.stop_recording().unwrap();
+        assert_eq!(text, "Ok!");
+
+        // Neither the viewport size nor the action has to be set up again
+        let mut text = String::new();
+        let result = recording.replay(&mut recording_ugui(), |ugui| frame(ugui, &mut text));
+        assert_eq!(result, Ok(()));
+        assert_eq!(text, "Ok!");
+
+        // A recording which is already running is left alone
+        ugui.start_recording();
+        let result = recording.replay(&mut ugui, |ugui| frame(ugui, &mut String::new()));
+        assert!(result.is_err());
+        assert!(ugui.is_recording());
+    }
+
+    fn recording_ugui() -> Ugui<RecordingStyler> {
+        Ugui::new(RecordingStyler::new())
+    }
+
+    #[test]
+    fn scrollbar_follows_drag() {
+        let mut ugui = recording_ugui();
+        let mut value = 0.0;
+        Script::new()
+            .move_to(5.0, 10.0)
+            .drag_to(5.0, 60.0)
+            .idle()
+            .run(&mut ugui, |ugui| {
+                value = ugui
+                    .scrollbar(
+                        Control {
+                            uid: 0,
+                            enabled: true,
+                            rect: Rect::new(0.0, 0.0, 10.0, 100.0),
+                        },
+                        Scrollbar { value, ratio: 0.5 },
+                    )
+                    .value;
+            });
+
+        assert!((value - 0.6).abs() < 1e-5);
+        assert!(matches!(
+            ugui.styler.calls_for(0).last(),
+            Some(StylerCall::Scrollbar { value, .. }) if (value - 0.6).abs() < 1e-5
+        ));
+    }
+
+    #[test]
+    fn listbox_selects_clicked_item() {
+        let items = vec!["a", "b", "c", "d", "e"];
+        let mut ugui = recording_ugui();
+        let mut index = None;
+        Script::new()
+            .click(50.0, 45.0)
+            .idle()
+            .run(&mut ugui, |ugui| {
+                index = ugui
+                    .listbox(
+                        Control {
+                            uid: 0,
+                            enabled: true,
+                            rect: Rect::new(0.0, 0.0, 100.0, 100.0),
+                        },
+                        Listbox {
+                            items: &items,
+                            index,
+                        },
+                    )
+                    .value;
+            });
+
+        assert_eq!(index, Some(2));
+        assert!(matches!(
+            ugui.styler.calls_for(0).last(),
+            Some(StylerCall::Listbox { index: Some(2), .. })
+        ));
+    }
+
+    #[test]
+    fn wheel_scrolls_innermost_hovered_area() {
+        let items = vec!["a"; 10];
+        let mut ugui = recording_ugui();
+        // The scroll values are checked right after the wheel turned, so they shouldn't trail behind
+        ugui.set_smooth_scrolling(false);
+        let mut frame = |ugui: &mut Ugui<RecordingStyler>| {
+            ugui.begin_scroll_area(Control {
+                uid: 100,
+                enabled: true,
+                rect: Rect::new(0.0, 0.0, 100.0, 100.0),
+            });
+            ugui.listbox(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 80.0, 60.0),
+                },
+                Listbox {
+                    items: &items,
+                    index: None,
+                },
+            );
+            ugui.label(
+                Control {
+                    uid: 1,
+                    enabled: true,
+                    rect: Rect::new(0.0, 180.0, 20.0, 20.0),
+                },
+                Default::default(),
+            );
+            ugui.end_scroll_area();
+        };
+        let scrollbar_value =
+            |ugui: &Ugui<RecordingStyler>, uid| match ugui.styler.calls_for(uid).last() {
+                Some(StylerCall::Scrollbar { value, .. }) => *value,
+                _ => panic!("scrollbar {uid} wasn't drawn"),
+            };
+
+        // Over the listbox, only the listbox scrolls down, by one step of its 200 high content in a 60 high viewport
+        Script::new()
+            .move_to(40.0, 30.0)
+            .wheel(-1)
+            .run(&mut ugui, &mut frame);
+        assert!((scrollbar_value(&ugui, 2) - 48.0 / 140.0).abs() < 1e-5);
+        assert_eq!(scrollbar_value(&ugui, 102), 0.0);
+        assert!(matches!(
+            ugui.styler.calls_for(0).last(),
+            Some(StylerCall::Listbox { scroll, .. }) if (scroll.y - 48.0 / 140.0).abs() < 1e-5
+        ));
+
+        // Next to the listbox, the scroll area scrolls instead, and scrolling up stops at the top
+        Script::new()
+            .move_to(82.0, 80.0)
+            .wheel(-1)
+            .wheel(2)
+            .run(&mut ugui, &mut frame);
+        assert_eq!(scrollbar_value(&ugui, 102), 0.0);
+        Script::new().wheel(-1).run(&mut ugui, &mut frame);
+        assert!((scrollbar_value(&ugui, 102) - 0.48).abs() < 1e-5);
+        assert!((scrollbar_value(&ugui, 2) - 48.0 / 140.0).abs() < 1e-5);
+    }
+
+    // Runs a frame with a table of two 60 wide columns and three rows, and returns its selection and sort
+    fn table_frame(
+        ugui: &mut Ugui<RecordingStyler>,
+        columns: &Vec<TableColumn>,
+        value: &mut (Option<usize>, Option<TableSort>),
+    ) {
+        let rows = vec![vec!["a", "b"], vec!["c", "d"], vec!["e", "f"]];
+        *value = ugui
+            .table(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 200.0, 100.0),
+                },
+                Table {
+                    columns,
+                    rows: &rows,
+                    index: value.0,
+                    sort: value.1,
+                },
+            )
+            .value;
+    }
+
+    #[test]
+    fn table_header_click_toggles_sort() {
+        let columns = vec![
+            TableColumn {
+                header: "A",
+                width: 60.0,
+            },
+            TableColumn {
+                header: "B",
+                width: 60.0,
+            },
+        ];
+        let mut ugui = recording_ugui();
+        let mut value = (None, None);
+        let sort = |column, direction| Some(TableSort { column, direction });
+
+        // Sorting waits for the release, like clicking a button
+        Script::new()
+            .move_to(20.0, 10.0)
+            .press()
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value.1, None);
+        Script::new()
+            .release()
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value.1, sort(0, SortDirection::Ascending));
+
+        Script::new()
+            .click(20.0, 10.0)
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value.1, sort(0, SortDirection::Descending));
+
+        Script::new()
+            .click(90.0, 10.0)
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value.1, sort(1, SortDirection::Ascending));
+
+        // Moving off the header before releasing cancels the click, and doesn't select anything either
+        Script::new()
+            .move_to(20.0, 10.0)
+            .drag_to(20.0, 60.0)
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value, (None, sort(1, SortDirection::Ascending)));
+    }
+
+    #[test]
+    fn table_divider_drag_is_clamped() {
+        let columns = vec![
+            TableColumn {
+                header: "A",
+                width: 60.0,
+            },
+            TableColumn {
+                header: "B",
+                width: 60.0,
+            },
+        ];
+        let mut ugui = recording_ugui();
+        let mut value = (None, None);
+        let column_widths = |ugui: &Ugui<RecordingStyler>| match ugui.styler.calls_for(0).last() {
+            Some(StylerCall::Table { column_widths, .. }) => column_widths.clone(),
+            _ => panic!("table wasn't drawn"),
+        };
+
+        Script::new()
+            .move_to(60.0, 10.0)
+            .drag_to(0.0, 10.0)
+            .idle()
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(column_widths(&ugui), vec![16.0, 60.0]);
+
+        // The resized width is kept by the table, even though the caller's columns didn't change
+        Script::new()
+            .move_to(16.0, 10.0)
+            .drag_to(100.0, 10.0)
+            .idle()
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(column_widths(&ugui), vec![100.0, 60.0]);
+        assert_eq!(value, (None, None));
+    }
+
+    #[test]
+    fn table_selects_clicked_and_dragged_rows() {
+        let columns = vec![TableColumn {
+            header: "A",
+            width: 60.0,
+        }];
+        let mut ugui = recording_ugui();
+        let mut value = (None, None);
+
+        Script::new()
+            .click(30.0, 50.0)
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value, (Some(1), None));
+
+        Script::new()
+            .move_to(30.0, 25.0)
+            .drag_to(30.0, 65.0)
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value, (Some(2), None));
+
+        // Clicking below the last row selects the last row
+        Script::new()
+            .click(30.0, 95.0)
+            .run(&mut ugui, |ugui| table_frame(ugui, &columns, &mut value));
+        assert_eq!(value, (Some(2), None));
+    }
+
+    // Nodes are labeled with their number, and the walks over the root nodes are counted
+    struct CountingTree {
+        roots: Vec<u64>,
+        children: HashMap<u64, Vec<u64>>,
+        root_walks: Cell<usize>,
+        revision: Cell<Option<u64>>,
+    }
+
+    impl TreeModel for CountingTree {
+        fn children(&self, node: Option<u64>) -> Vec<u64> {
+            match node {
+                None => {
+                    self.root_walks.set(self.root_walks.get() + 1);
+                    self.roots.clone()
+                }
+                Some(node) => self.children.get(&node).cloned().unwrap_or_default(),
+            }
+        }
+        fn label(&self, node: u64) -> String {
+            node.to_string()
+        }
+        fn revision(&self) -> Option<u64> {
+            self.revision.get()
+        }
+    }
+
+    fn tree_view_frame(
+        ugui: &mut Ugui<RecordingStyler>,
+        model: &CountingTree,
+        rect: Rect,
+        selected: &mut Option<u64>,
+    ) {
+        *selected = ugui
+            .tree_view(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect,
+                },
+                TreeView {
+                    model,
+                    selected: *selected,
+                },
+            )
+            .value;
+    }
+
+    #[test]
+    fn tree_view_expands_and_navigates_with_keys() {
+        let model = CountingTree {
+            roots: vec![1, 2, 3],
+            children: HashMap::from([(1, vec![10, 11])]),
+            root_walks: Cell::new(0),
+            revision: Cell::new(Some(0)),
+        };
+        let rect = Rect::new(0.0, 0.0, 100.0, 200.0);
+        let mut ugui = recording_ugui();
+        let mut selected = None;
+        let mut run = |ugui: &mut Ugui<RecordingStyler>, script: Script| {
+            script.run(ugui, |ugui| {
+                tree_view_frame(ugui, &model, rect, &mut selected)
+            });
+            selected
+        };
+        let shown_nodes = |ugui: &Ugui<RecordingStyler>| -> Vec<u64> {
+            // The focus ring is drawn after the tree view
+            ugui.styler
+                .calls_for(0)
+                .iter()
+                .rev()
+                .find_map(|x| match x {
+                    StylerCall::TreeView { rows, .. } => {
+                        Some(rows.iter().map(|x| x.node).collect())
+                    }
+                    _ => None,
+                })
+                .expect("tree view wasn't drawn")
+        };
+
+        // Clicking the expander shows the children, without selecting anything
+        assert_eq!(run(&mut ugui, Script::new().click(5.0, 10.0).idle()), None);
+        assert_eq!(shown_nodes(&ugui), vec![1, 10, 11, 2, 3]);
+
+        assert_eq!(run(&mut ugui, Script::new().click(50.0, 70.0)), Some(2));
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Up)), Some(11));
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Up)), Some(10));
+        // Left goes to the parent first, and collapses it next
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Left)), Some(1));
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Left)), Some(1));
+        assert_eq!(shown_nodes(&ugui), vec![1, 2, 3]);
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Down)), Some(2));
+        assert_eq!(run(&mut ugui, Script::new().key(Key::End)), Some(3));
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Home)), Some(1));
+        // Right expands the node first, and goes to its first child next
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Right)), Some(1));
+        assert_eq!(shown_nodes(&ugui), vec![1, 10, 11, 2, 3]);
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Right)), Some(10));
+    }
+
+    #[test]
+    fn tree_view_scrolls_selection_into_view_and_walks_visible_rows() {
+        let model = CountingTree {
+            roots: (0..100).collect(),
+            children: HashMap::new(),
+            root_walks: Cell::new(0),
+            revision: Cell::new(Some(0)),
+        };
+        // Five of the hundred rows fit into the tree view
+        let rect = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let mut ugui = recording_ugui();
+        ugui.set_smooth_scrolling(false);
+        let mut selected = None;
+        let mut run = |ugui: &mut Ugui<RecordingStyler>, script: Script| {
+            script.run(ugui, |ugui| {
+                tree_view_frame(ugui, &model, rect, &mut selected)
+            });
+            selected
+        };
+        let scrollbar_value = |ugui: &Ugui<RecordingStyler>| match ugui.styler.calls_for(2).last() {
+            Some(StylerCall::Scrollbar { value, .. }) => *value,
+            _ => panic!("scrollbar wasn't drawn"),
+        };
+
+        assert_eq!(run(&mut ugui, Script::new().click(50.0, 10.0)), Some(0));
+
+        // The scrollbar is placed after the key was handled, so it shows the new position in the same frame
+        assert_eq!(run(&mut ugui, Script::new().key(Key::End)), Some(99));
+        assert_eq!(scrollbar_value(&ugui), 1.0);
+        assert!(ugui.styler.calls_for(0).iter().any(|x| matches!(
+            x,
+            StylerCall::TreeView { rows, .. } if rows.last().is_some_and(|x| x.node == 99)
+        )));
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Up)), Some(98));
+        assert_eq!(scrollbar_value(&ugui), 1.0);
+        assert_eq!(run(&mut ugui, Script::new().key(Key::Home)), Some(0));
+        assert_eq!(scrollbar_value(&ugui), 0.0);
+
+        // The row count is kept from earlier frames, so only the visible rows are walked
+        model.root_walks.set(0);
+        run(&mut ugui, Script::new().idle());
+        assert_eq!(model.root_walks.get(), 1);
+
+        // Without a revision, all rows are counted again every frame
+        model.revision.set(None);
+        model.root_walks.set(0);
+        run(&mut ugui, Script::new().idle().idle());
+        assert_eq!(model.root_walks.get(), 4);
+    }
+
+    // The recording styler lays text out in monospace, so the click lands between the second and third char
+    #[test]
+    fn textbox_places_caret_at_click() {
+        let mut ugui = recording_ugui();
+        let mut text = "hello".to_string();
+        Script::new()
+            .click(10.0 + CHAR_WIDTH * 2.0 + 1.0, 15.0)
+            .type_text("X")
+            .run(&mut ugui, |ugui| {
+                text = ugui
+                    .textbox(
+                        Control {
+                            uid: 0,
+                            enabled: true,
+                            rect: Rect::new(10.0, 10.0, 100.0, 20.0),
+                        },
+                        Textbox { text: &text },
+                    )
+                    .value;
+            });
+
+        assert_eq!(text, "heXllo");
+    }
+
+    #[test]
+    fn textbox_draws_caret_where_this_frames_input_left_it() {
+        let mut ugui = recording_ugui();
+        let mut text = "hello".to_string();
+        let mut frame = |ugui: &mut Ugui<RecordingStyler>| {
+            text = ugui
+                .textbox(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(10.0, 10.0, 100.0, 20.0),
+                    },
+                    Textbox { text: &text },
+                )
+                .value;
+        };
+        let drawn_caret = |ugui: &Ugui<RecordingStyler>| {
+            ugui.styler.calls_for(0).iter().rev().find_map(|x| match x {
+                StylerCall::Textbox { caret, .. } => Some(*caret),
+                _ => None,
+            })
+        };
+
+        Script::new().click(12.0, 15.0).run(&mut ugui, &mut frame);
+        assert_eq!(drawn_caret(&ugui), Some(Some(0)));
+        Script::new().type_text("ab").run(&mut ugui, &mut frame);
+        assert_eq!(drawn_caret(&ugui), Some(Some(2)));
+        Script::new().key(Key::Left).run(&mut ugui, &mut frame);
+        assert_eq!(drawn_caret(&ugui), Some(Some(1)));
+
+        // Unfocused textboxes don't show a caret
+        Script::new()
+            .click(200.0, 200.0)
+            .idle()
+            .run(&mut ugui, &mut frame);
+        assert_eq!(drawn_caret(&ugui), Some(None));
+    }
+
+    #[test]
+    fn focused_textbox_output() {
+        let mut ugui = recording_ugui();
+        let mut text = "hello".to_string();
+        let mut frame = |ugui: &mut Ugui<RecordingStyler>| {
+            text = ugui
+                .textbox(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(10.0, 10.0, 100.0, 20.0),
+                    },
+                    Textbox { text: &text },
+                )
+                .value;
+        };
+
+        let output = Script::new()
+            .click(12.0, 15.0)
+            .type_text("ab")
+            .run(&mut ugui, &mut frame);
+        assert!(output.text_input);
+        assert!(output.repaint);
+        assert_eq!(output.cursor, CursorIcon::Text);
+        assert_eq!(
+            output.ime_rect,
+            Some(Rect::new(10.0 + CHAR_WIDTH * 2.0, 10.0, 1.0, 16.0))
+        );
+
+        // Nothing changes without new input, so there's no need for another frame
+        let output = Script::new().idle().run(&mut ugui, &mut frame);
+        assert!(output.text_input);
+        assert!(!output.repaint);
+        assert_eq!(output.cursor, CursorIcon::Default);
+    }
+
+    #[test]
+    fn textbox_caret_survives_text_replaced_by_caller() {
+        let mut ugui = recording_ugui();
+        let mut text = "abc".to_string();
+        let frame = |ugui: &mut Ugui<RecordingStyler>, text: &mut String| {
+            *text = ugui
+                .textbox(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 100.0, 20.0),
+                    },
+                    Textbox { text },
+                )
+                .value;
+        };
+
+        // Puts the caret after the "a"
+        Script::new()
+            .click(CHAR_WIDTH, 5.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut text));
+
+        // The caret's byte offset is now in the middle of the "é"
+        text = "é".to_string();
+        Script::new()
+            .type_text("x")
+            .run(&mut ugui, |ugui| frame(ugui, &mut text));
+        assert_eq!(text, "xé");
+    }
+
+    #[test]
+    fn frames_are_only_needed_after_changes() {
+        let mut ugui = recording_ugui();
+        let idle = Input {
+            mouse_position: Point { x: 5.0, y: 5.0 },
+            time: 0.1,
+            ..Default::default()
+        };
+        assert!(ugui.needs_frame(&idle));
+
+        let output = Script::new()
+            .move_to(5.0, 5.0)
+            .click(5.0, 5.0)
+            .run(&mut ugui, |ugui| {
+                ugui.button(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 20.0, 20.0),
+                    },
+                    Default::default(),
+                );
+            });
+        // The button looks hovered instead of pushed after the release, which only shows in the next frame
+        assert!(output.repaint);
+        assert!(ugui.needs_frame(&idle));
+
+        // Taking the focus away from the button is the last change
+        Script::new()
+            .move_to(5.0, 5.0)
+            .idle()
+            .run(&mut ugui, |_| {});
+        assert!(!ugui.needs_frame(&idle));
+        assert!(ugui.needs_frame(&Input {
+            text: "a".to_string(),
+            ..idle.clone()
+        }));
+        assert!(ugui.needs_frame(&Input {
+            primary_down: true,
+            ..idle.clone()
+        }));
+
+        ugui.request_repaint_after(1.0);
+        assert!(!ugui.needs_frame(&idle));
+        assert!(ugui.needs_frame(&Input {
+            time: 1.5,
+            ..idle.clone()
+        }));
+    }
+
+    // Clicks at the point and tells whether a button covering 0, 0 to 100, 100 inside the clips was clicked
+    fn button_clicked_in_clips(clips: &[Rect], point: Point) -> bool {
+        let mut ugui = recording_ugui();
+        let mut clicked = false;
+        Script::new()
+            .click(point.x, point.y)
+            .run(&mut ugui, |ugui| {
+                for clip in clips {
+                    ugui.push_clip(*clip);
+                }
+                clicked = ugui
+                    .button(
+                        Control {
+                            uid: 0,
+                            enabled: true,
+                            rect: Rect::new(0.0, 0.0, 100.0, 100.0),
+                        },
+                        Default::default(),
+                    )
+                    .clicked;
+                for _ in clips {
+                    ugui.pop_clip();
+                }
+            });
+        clicked
+    }
+
+    #[test]
+    fn clipped_controls_cant_be_clicked() {
+        // Nested clips only leave their intersection from 25, 25 to 50, 50
+        let nested = [
+            Rect::new(0.0, 0.0, 50.0, 50.0),
+            Rect::new(25.0, 25.0, 50.0, 50.0),
+        ];
+        assert!(button_clicked_in_clips(&nested, Point { x: 30.0, y: 30.0 }));
+        assert!(!button_clicked_in_clips(
+            &nested,
+            Point { x: 10.0, y: 10.0 }
+        ));
+        assert!(!button_clicked_in_clips(
+            &nested,
+            Point { x: 60.0, y: 60.0 }
+        ));
+
+        // Clips which don't overlap leave nothing to click
+        let disjoint = [
+            Rect::new(0.0, 0.0, 20.0, 20.0),
+            Rect::new(40.0, 40.0, 20.0, 20.0),
+        ];
+        for point in [Point { x: 10.0, y: 10.0 }, Point { x: 50.0, y: 50.0 }] {
+            assert!(!button_clicked_in_clips(&disjoint, point));
+        }
+
+        // Only the top half of the button is inside the scroll area
+        for (y, expected) in [(45.0, true), (55.0, false)] {
+            let mut ugui = recording_ugui();
+            let mut clicked = false;
+            Script::new().click(10.0, y).run(&mut ugui, |ugui| {
+                ugui.begin_scroll_area(Control {
+                    uid: 10,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 100.0, 50.0),
+                });
+                clicked = ugui
+                    .button(
+                        Control {
+                            uid: 0,
+                            enabled: true,
+                            rect: Rect::new(0.0, 0.0, 20.0, 100.0),
+                        },
+                        Default::default(),
+                    )
+                    .clicked;
+                ugui.end_scroll_area();
+            });
+            assert_eq!(clicked, expected);
+        }
+    }
+
+    // Runs the script with a button covering 0, 0 to 20, 20 and returns its response in every frame
+    fn button_responses(script: Script, mode: ButtonMode) -> Vec<Response> {
+        let mut ugui = recording_ugui();
+        let mut responses = vec![];
+        script.run(&mut ugui, |ugui| {
+            responses.push(ugui.button(
+                Control {
+                    uid: 0,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 20.0, 20.0),
+                },
+                Button { text: "", mode },
+            ));
+        });
+        responses
+    }
+
+    #[test]
+    fn button_modes_decide_when_clicks_happen() {
+        let clicks = |responses: Vec<Response>| -> Vec<bool> {
+            responses.iter().map(|x| x.clicked).collect()
+        };
+
+        // Released over the button, clicked once the mouse goes up
+        let responses = button_responses(Script::new().click(10.0, 10.0), ButtonMode::Release);
+        assert_eq!(clicks(responses), vec![false, false, true]);
+
+        // Moving away before releasing cancels the click
+        let responses = button_responses(
+            Script::new()
+                .move_to(10.0, 10.0)
+                .press()
+                .move_to(50.0, 50.0)
+                .release(),
+            ButtonMode::Release,
+        );
+        assert_eq!(clicks(responses), vec![false; 4]);
+
+        // Press mode fires right away, even if the mouse is released somewhere else
+        let responses = button_responses(
+            Script::new()
+                .move_to(10.0, 10.0)
+                .press()
+                .move_to(50.0, 50.0)
+                .release(),
+            ButtonMode::Press,
+        );
+        assert_eq!(clicks(responses), vec![false, true, false, false]);
+    }
+
+    #[test]
+    fn button_counts_multi_clicks() {
+        let script = Script::new()
+            .click(10.0, 10.0)
+            .click(10.0, 10.0)
+            .click(11.0, 10.0)
+            // Too late to continue the succession, so this starts over
+            .wait(1.0)
+            .click(10.0, 10.0)
+            // Time going backwards, e.g. after the host's clock was reset, doesn't continue it either
+            .wait(-10.0)
+            .click(10.0, 10.0);
+        let responses: Vec<(bool, bool)> = button_responses(script, ButtonMode::Release)
+            .iter()
+            .filter(|x| x.pressed)
+            .map(|x| (x.double_clicked, x.triple_clicked))
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                (false, false),
+                (true, false),
+                (false, true),
+                (false, false),
+                (false, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn button_reports_drag_delta_past_threshold() {
+        let script = Script::new()
+            .move_to(5.0, 5.0)
+            .press()
+            .move_to(7.0, 5.0)
+            .move_to(15.0, 5.0)
+            .move_to(18.0, 7.0)
+            .release();
+        let deltas: Vec<Point> = button_responses(script, ButtonMode::Release)
+            .iter()
+            .map(|x| x.drag_delta)
+            .collect();
+
+        // Movement within the drag threshold is held back, then reported all at once
+        assert_eq!(
+            deltas,
+            vec![
+                Point::default(),
+                Point::default(),
+                Point::default(),
+                Point { x: 10.0, y: 0.0 },
+                Point { x: 3.0, y: 2.0 },
+                Point::default(),
+            ]
+        );
+    }
+
+    #[test]
+    fn button_response_reports_hover_secondary_clicks_and_focus() {
+        let script = Script::new()
+            .move_to(50.0, 50.0)
+            .move_to(10.0, 10.0)
+            .press_button(MouseButton::Secondary)
+            .release_button(MouseButton::Secondary)
+            .key(Key::Tab)
+            // Clicking into empty space takes the focus away again
+            .move_to(50.0, 50.0)
+            .press()
+            .idle();
+        let responses = button_responses(script, ButtonMode::Release);
+
+        let hovered: Vec<bool> = responses.iter().map(|x| x.hovered).collect();
+        assert_eq!(
+            hovered,
+            vec![false, true, true, true, true, false, false, false]
+        );
+        let secondary_clicked: Vec<bool> = responses.iter().map(|x| x.secondary_clicked).collect();
+        assert_eq!(
+            secondary_clicked,
+            vec![false, false, false, true, false, false, false, false]
+        );
+        assert!(responses.iter().all(|x| !x.clicked));
+
+        // Focus changes show up in the frame after they happen, as the focus is decided at the end of a frame
+        let focus: Vec<(bool, bool)> = responses
+            .iter()
+            .map(|x| (x.gained_focus, x.lost_focus))
+            .collect();
+        assert_eq!(focus[5], (true, false));
+        assert_eq!(focus[7], (false, true));
+        assert_eq!(responses[3].rect, Rect::new(0.0, 0.0, 20.0, 20.0));
+    }
+
+    #[test]
+    fn input_accumulator_reports_events_once_per_frame() {
+        let mut accumulator = InputAccumulator::new();
+        accumulator.mouse_moved(Point { x: 10.0, y: 20.0 });
+        accumulator.button_changed(MouseButton::Primary, true);
+        // The wheel can't turn further than what fits into the input
+        accumulator.wheel_turned(100.0);
+        accumulator.wheel_turned(100.0);
+        // Holding a key repeats it
+        accumulator.key_pressed(Key::Backspace);
+        accumulator.key_pressed(Key::Backspace);
+        accumulator.text_typed("a\rb");
+
+        let input = accumulator.take_input();
+        assert_eq!(input.mouse_position, Point { x: 10.0, y: 20.0 });
+        assert!(input.primary_down);
+        assert_eq!(input.mouse_wheel, i8::MAX);
+        assert_eq!(input.pressed_keys, vec![Key::Backspace, Key::Backspace]);
+        assert_eq!(input.text, "ab");
+
+        // Dragging continues outside the window, until the button is released there
+        accumulator.mouse_left();
+        let next_input = accumulator.take_input();
+        assert_eq!(next_input.mouse_position, Point { x: 10.0, y: 20.0 });
+        assert_eq!(next_input.mouse_wheel, 0);
+        assert!(next_input.pressed_keys.is_empty());
+        assert!(next_input.text.is_empty());
+        assert!(next_input.time >= input.time);
+
+        accumulator.modifiers_changed(Modifiers {
+            shift: true,
+            ..Default::default()
+        });
+        accumulator.focus_lost();
+        accumulator.mouse_left();
+        let input = accumulator.take_input();
+        assert!(!input.primary_down);
+        assert_eq!(input.modifiers, Modifiers::default());
+        assert!(input.mouse_position.x < 0.0);
+    }
+
+    #[test]
+    fn tab_traverses_enabled_controls_and_wraps_around() {
+        let mut ugui = recording_ugui();
+        // Runs the script and gets the uid of the control with the focus ring in its last frame
+        let mut focused_after = |script: Script| {
+            script.idle().run(&mut ugui, |ugui| {
+                ugui.styler.clear();
+                for (uid, enabled) in [(1, true), (2, false), (3, true), (4, true)] {
+                    ugui.button(
+                        Control {
+                            uid,
+                            enabled,
+                            rect: Rect::new(uid as f32 * 30.0, 0.0, 20.0, 20.0),
+                        },
+                        Default::default(),
+                    );
+                }
+            });
+            ugui.styler.calls.iter().find_map(|x| match x {
+                StylerCall::FocusRing(control) => Some(control.uid),
+                _ => None,
+            })
+        };
+        let shift = Modifiers {
+            shift: true,
+            ..Default::default()
+        };
+
+        assert_eq!(focused_after(Script::new()), None);
+        assert_eq!(focused_after(Script::new().key(Key::Tab)), Some(1));
+        // The disabled button is skipped
+        assert_eq!(focused_after(Script::new().key(Key::Tab)), Some(3));
+        assert_eq!(focused_after(Script::new().key(Key::Tab)), Some(4));
+        assert_eq!(focused_after(Script::new().key(Key::Tab)), Some(1));
+        assert_eq!(
+            focused_after(Script::new().modifiers(shift).key(Key::Tab)),
+            Some(4)
+        );
+        assert_eq!(
+            focused_after(Script::new().modifiers(shift).key(Key::Tab)),
+            Some(3)
+        );
+        assert_eq!(
+            focused_after(Script::new().modifiers(shift).key(Key::Tab)),
+            Some(1)
+        );
+
+        // Without a focused control, shift tab starts at the end
+        assert_eq!(focused_after(Script::new().click(200.0, 200.0)), None);
+        assert_eq!(
+            focused_after(Script::new().modifiers(shift).key(Key::Tab)),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn scaled_input_hits_logical_controls() {
+        let mut ugui = recording_ugui();
+        ugui.set_scale(2.0);
+        let frame = |ugui: &mut Ugui<RecordingStyler>, clicked: &mut bool| {
+            ugui.push_clip(Rect::new(0.0, 0.0, 20.0, 20.0));
+            *clicked = ugui
+                .button(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(10.0, 0.0, 20.0, 20.0),
+                    },
+                    Default::default(),
+                )
+                .clicked;
+            ugui.pop_clip();
+        };
+        let mut clicked = false;
+
+        // The input is in physical pixels, so this lands at 15, 10 inside the visible part of the button
+        Script::new()
+            .click(30.0, 20.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut clicked));
+        assert!(clicked);
+        assert!(ugui
+            .styler
+            .calls
+            .contains(&StylerCall::SetClip(Some(Rect::new(0.0, 0.0, 20.0, 20.0)))));
+
+        // Left of the button at 7.5, 10, and on the clipped away part of the button at 25, 10
+        for x in [15.0, 50.0] {
+            Script::new()
+                .click(x, 20.0)
+                .run(&mut ugui, |ugui| frame(ugui, &mut clicked));
+            assert!(!clicked);
+        }
+    }
+
+    #[test]
+    fn frames_are_not_needed_for_unchanged_input_when_scaled() {
+        let mut ugui = recording_ugui();
+        ugui.set_scale(1.5);
+        let idle = Script::new().move_to(15.0, 15.0).idle();
+        idle.run(&mut ugui, |_| {});
+
+        let input = idle.inputs().last().unwrap();
+        assert!(!ugui.needs_frame(input));
+        assert!(ugui.needs_frame(&Input {
+            mouse_position: Point { x: 16.0, y: 15.0 },
+            ..input.clone()
+        }));
+    }
+
+    #[cfg(feature = "software")]
+    #[test]
+    fn wheel_scrolling_is_spread_over_frames() {
+        let mut ugui = software_ugui();
+        let mut label_y = 0.0;
+        let frame = |ugui: &mut Ugui<StandardStyler<SoftwarePainter>>, label_y: &mut f32| {
+            ugui.begin_scroll_area(Control {
+                uid: 0,
+                enabled: true,
+                rect: Rect::new(0.0, 0.0, 100.0, 50.0),
+            });
+            *label_y = ugui
+                .label(
+                    Control {
+                        uid: 3,
+                        enabled: true,
+                        rect: Rect::new(0.0, 200.0, 20.0, 20.0),
+                    },
+                    Default::default(),
+                )
+                .rect
+                .y;
+            ugui.end_scroll_area();
+        };
+
+        // One step scrolls the content up by 48, of which only part is covered in the first frame
+        // The content size is only known once the scroll area was placed for a frame
+        let script = Script::new().move_to(50.0, 25.0).idle().wheel(-1);
+        let output = script.run(&mut ugui, |ugui| frame(ugui, &mut label_y));
+        assert!(label_y < 200.0 && label_y > 152.0);
+        assert!(output.repaint);
+
+        // Without time passing, the animation doesn't advance
+        let input = Input {
+            mouse_wheel: 0,
+            ..script.inputs().last().unwrap().clone()
+        };
+        let last_y = label_y;
+        ugui.begin(input);
+        frame(&mut ugui, &mut label_y);
+        ugui.end();
+        assert_eq!(label_y, last_y);
+
+        let mut script = Script::new();
+        for _ in 0..60 {
+            script = script.idle();
+        }
+        let output = script.run(&mut ugui, |ugui| frame(ugui, &mut label_y));
+        assert!((label_y - 152.0).abs() < 0.1);
+        assert!(!output.repaint);
+    }
+
+    #[test]
+    fn animations_fade_and_blink_over_time() {
+        let black = Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        let white = Color {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        };
+        let mut animations = Animations::default();
+
+        animations.begin(0.0, 0.0);
+        assert_eq!(animations.fade(0, 0, black), black);
+        assert!(animations.caret_visible(1, 0));
+        animations.end();
+        // Nothing fades yet, but the caret blinks after half a second
+        assert_eq!(animations.repaint_at(), Some(0.5));
+
+        animations.begin(0.1, 0.1);
+        assert_eq!(animations.fade(0, 0, white), black);
+        assert!(animations.caret_visible(1, 0));
+        animations.end();
+        assert_eq!(animations.repaint_at(), Some(0.1));
+
+        animations.begin(0.175, 0.075);
+        assert_eq!(animations.fade(0, 0, white).r, 128);
+        assert!(animations.caret_visible(1, 0));
+        animations.end();
+
+        animations.begin(0.6, 0.425);
+        assert_eq!(animations.fade(0, 0, white), white);
+        assert!(!animations.caret_visible(1, 0));
+        animations.end();
+        assert_eq!(animations.repaint_at(), Some(1.0));
+
+        // Moving the caret shows it again right away
+        animations.begin(0.7, 0.1);
+        assert!(animations.caret_visible(1, 1));
+        animations.end();
+        assert_eq!(animations.repaint_at(), Some(1.2));
+    }
+
+    #[test]
+    fn debug_overlay_reports_collisions_and_hover() {
+        let mut ugui = recording_ugui();
+        ugui.set_debug_overlay(true);
+        Script::new().move_to(70.0, 10.0).run(&mut ugui, |ugui| {
+            for (uid, x) in [(5, 0.0), (5, 30.0), (6, 60.0)] {
+                ugui.button(
+                    Control {
+                        uid,
+                        enabled: true,
+                        rect: Rect::new(x, 0.0, 20.0, 20.0),
+                    },
+                    Default::default(),
+                );
+            }
+        });
+
+        let Some(StylerCall::DebugOverlay(overlay)) = ugui.styler.calls.iter().rev().nth(1) else {
+            panic!("debug overlay wasn't drawn before end");
+        };
+        let collisions: Vec<(i64, bool)> = overlay
+            .controls
+            .iter()
+            .map(|x| (x.uid, x.collides))
+            .collect();
+        assert_eq!(collisions, vec![(5, true), (5, true), (6, false)]);
+        assert_eq!(overlay.hovered_control, Some(6));
+        assert!(overlay.hovered_state.is_some());
+        assert_eq!(overlay.draw_stats.draw_calls, 3);
+    }
+
+    #[test]
+    fn access_tree_and_actions() {
+        use ugui::access::{AccessAction, AccessRole};
+
+        let mut ugui = recording_ugui();
+        ugui.set_accessibility(true);
+        let mut text = "abc".to_string();
+        let mut frame = |ugui: &mut Ugui<RecordingStyler>| {
+            ugui.begin(Default::default());
+            let clicked = ugui
+                .button(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 50.0, 20.0),
+                    },
+                    Button {
+                        text: "OK",
+                        ..Default::default()
+                    },
+                )
+                .clicked;
+            text = ugui
+                .textbox(
+                    Control {
+                        uid: 1,
+                        enabled: true,
+                        rect: Rect::new(0.0, 30.0, 100.0, 20.0),
+                    },
+                    Textbox { text: &text },
+                )
+                .value;
+            ugui.end();
+            clicked
+        };
+
+        assert!(!frame(&mut ugui));
+        let nodes = ugui.access_nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(
+            (nodes[0].role, nodes[0].name.as_str()),
+            (AccessRole::Button, "OK")
+        );
+        assert_eq!(nodes[1].value.as_deref(), Some("abc"));
+        assert_eq!(nodes[1].bounds, Rect::new(0.0, 30.0, 100.0, 20.0));
+
+        ugui.queue_access_action(0, AccessAction::Press);
+        ugui.queue_access_action(1, AccessAction::Focus);
+        ugui.queue_access_action(1, AccessAction::SetValue("xyz".to_string()));
+        assert!(frame(&mut ugui));
+        assert_eq!(text, "xyz");
+        assert!(ugui.access_nodes()[1].focused);
+    }
+
+    #[cfg(feature = "accesskit")]
+    #[test]
+    fn accesskit_tree_nests_window_controls() {
+        use accesskit::NodeId;
+        use ugui::accesskit_adapter::tree_update;
+
+        let mut ugui = recording_ugui();
+        ugui.set_accessibility(true);
+        let button = |ugui: &mut Ugui<RecordingStyler>, uid: i64| {
+            ugui.button(
+                Control {
+                    uid,
+                    enabled: true,
+                    rect: Rect::new(0.0, 0.0, 20.0, 20.0),
+                },
+                Default::default(),
+            );
+        };
+        Script::new().idle().run(&mut ugui, |ugui| {
+            button(ugui, -1);
+            button(ugui, i64::MIN);
+            ugui.begin_window(100, "Window", &mut Rect::new(50.0, 50.0, 100.0, 100.0));
+            button(ugui, 200);
+            ugui.end_window();
+        });
+
+        let update = tree_update(&ugui);
+        let children = |id: NodeId| {
+            update
+                .nodes
+                .iter()
+                .find(|x| x.0 == id)
+                .map(|x| x.1.children().to_vec())
+                .unwrap()
+        };
+        let root = update.tree.as_ref().unwrap().root;
+
+        // The control with the root's reserved uid is left out, while uid -1 is an ordinary control
+        assert_eq!(children(root), vec![NodeId(-1i64 as u64), NodeId(100)]);
+        assert_eq!(update.nodes.iter().filter(|x| x.0 == root).count(), 1);
+        assert!(children(NodeId(100)).contains(&NodeId(200)));
+        assert!(children(NodeId(-1i64 as u64)).is_empty());
+    }
+
+    // Both windows contain a button at the same screen position, so only the window in front may get the click
+    #[test]
+    fn front_window_gets_the_click() {
+        let mut ugui = recording_ugui();
+        let mut clicked = vec![];
+        let mut frame = |ugui: &mut Ugui<RecordingStyler>| {
+            for (uid, x) in [(100, 0.0), (200, 50.0)] {
+                let mut rect = Rect::new(x, x, 100.0, 100.0);
+                ugui.begin_window(uid, "", &mut rect);
+                let button_rect = Rect::new(50.0 - x, 50.0 - x, 40.0, 40.0);
+                if ugui
+                    .button(
+                        Control {
+                            uid: uid + 3,
+                            enabled: true,
+                            rect: button_rect,
+                        },
+                        Default::default(),
+                    )
+                    .clicked
+                {
+                    clicked.push(uid);
+                }
+                ugui.end_window();
+            }
+        };
+
+        Script::new()
+            .idle()
+            .click(60.0, 80.0)
+            .click(10.0, 10.0)
+            .click(60.0, 80.0)
+            .run(&mut ugui, &mut frame);
+        assert_eq!(clicked, vec![200, 100]);
+    }
+
+    #[test]
+    fn closed_window_doesnt_block_controls_below() {
+        let mut ugui = recording_ugui();
+        let frame = |ugui: &mut Ugui<RecordingStyler>, window_open: bool| {
+            let clicked = ugui
+                .button(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 100.0, 100.0),
+                    },
+                    Default::default(),
+                )
+                .clicked;
+            if window_open {
+                ugui.begin_window(1, "", &mut Rect::new(0.0, 0.0, 100.0, 100.0));
+                ugui.end_window();
+            }
+            clicked
+        };
+
+        let mut clicked = false;
+        Script::new()
+            .idle()
+            .click(50.0, 50.0)
+            .run(&mut ugui, |ugui| clicked |= frame(ugui, true));
+        assert!(!clicked);
+
+        Script::new()
+            .idle()
+            .click(50.0, 50.0)
+            .run(&mut ugui, |ugui| clicked |= frame(ugui, false));
+        assert!(clicked);
+    }
+
+    #[test]
+    fn window_moves_resizes_and_stays_in_viewport() {
+        let mut rect = Rect::new(10.0, 10.0, 100.0, 80.0);
+        let mut ugui = recording_ugui();
+        let frame = |ugui: &mut Ugui<RecordingStyler>, rect: &mut Rect| {
+            ugui.begin_window(0, "Window", rect);
+            ugui.end_window();
+        };
+
+        Script::new()
+            .move_to(30.0, 15.0)
+            .drag_to(60.0, 45.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut rect));
+        assert_eq!(rect, Rect::new(40.0, 40.0, 100.0, 80.0));
+
+        Script::new()
+            .move_to(139.0, 119.0)
+            .drag_to(159.0, 139.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut rect));
+        assert_eq!(rect, Rect::new(40.0, 40.0, 120.0, 100.0));
+
+        ugui.set_viewport_size(Size::new(150.0, 120.0));
+        Script::new()
+            .idle()
+            .run(&mut ugui, |ugui| frame(ugui, &mut rect));
+        assert_eq!(rect, Rect::new(30.0, 20.0, 120.0, 100.0));
+    }
+
+    #[test]
+    fn message_box_blocks_input_until_answered() {
+        let mut ugui = recording_ugui();
+        ugui.set_viewport_size(Size::new(400.0, 300.0));
+        let mut results = vec![];
+        let mut background_clicks = 0;
+        let mut frame = |ugui: &mut Ugui<RecordingStyler>, open: bool| {
+            if ugui
+                .button(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 400.0, 300.0),
+                    },
+                    Default::default(),
+                )
+                .clicked
+            {
+                background_clicks += 1;
+            }
+            if open {
+                results.extend(ugui.message_box(
+                    10,
+                    "Quit",
+                    "Save changes?",
+                    MessageBoxButtons::OkCancel,
+                ));
+            }
+        };
+
+        // The box is centered, with its buttons at the bottom right
+        Script::new()
+            .idle()
+            .click(10.0, 10.0)
+            .click(200.0, 195.0)
+            .run(&mut ugui, |ugui| frame(ugui, true));
+        Script::new()
+            .click(10.0, 10.0)
+            .run(&mut ugui, |ugui| frame(ugui, false));
+        Script::new()
+            .idle()
+            .key(Key::Escape)
+            .run(&mut ugui, |ugui| frame(ugui, true));
+
+        assert!(ugui
+            .styler
+            .calls
+            .contains(&StylerCall::ModalOverlay(Rect::new(0.0, 0.0, 400.0, 300.0))));
+        assert_eq!(background_clicks, 1);
+        assert_eq!(
+            results,
+            vec![MessageBoxResult::Ok, MessageBoxResult::Cancel]
+        );
+    }
+
+    #[test]
+    fn modal_takes_keyboard_and_leaves_nothing_behind_once_closed() {
+        let mut ugui = recording_ugui();
+        ugui.set_viewport_size(Size::new(400.0, 300.0));
+        let frame = |ugui: &mut Ugui<RecordingStyler>, open: bool, clicks: &mut usize| {
+            if ugui
+                .button(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 400.0, 300.0),
+                    },
+                    Default::default(),
+                )
+                .clicked
+            {
+                *clicks += 1;
+            }
+            if open {
+                ugui.message_box(10, "Quit", "Save changes?", MessageBoxButtons::Ok);
+            }
+        };
+
+        // Enter doesn't reach the button focused before the box opened
+        let mut clicks = 0;
+        Script::new()
+            .key(Key::Tab)
+            .run(&mut ugui, |ugui| frame(ugui, false, &mut clicks));
+        Script::new()
+            .idle()
+            .key(Key::Enter)
+            .key(Key::Space)
+            .run(&mut ugui, |ugui| frame(ugui, true, &mut clicks));
+        assert_eq!(clicks, 0);
+
+        // Once the box is gone, the area it covered can be clicked right away
+        Script::new()
+            .click(200.0, 150.0)
+            .run(&mut ugui, |ugui| frame(ugui, false, &mut clicks));
+        assert_eq!(clicks, 1);
+    }
+
+    #[test]
+    fn splitter_drags_clamps_and_collapses() {
+        let mut ugui = recording_ugui();
+        let mut panes = SplitterPanes::default();
+        let frame = |ugui: &mut Ugui<RecordingStyler>, panes: &mut SplitterPanes| {
+            *panes = ugui
+                .splitter(
+                    Control {
+                        uid: 0,
+                        enabled: true,
+                        rect: Rect::new(0.0, 0.0, 206.0, 100.0),
+                    },
+                    Splitter {
+                        min_sizes: [50.0, 40.0],
+                        collapsible: Some(SplitterPane::First),
+                        ..Default::default()
+                    },
+                )
+                .value;
+        };
+
+        let output = Script::new()
+            .move_to(103.0, 50.0)
+            .drag_to(133.0, 50.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut panes));
+        assert_eq!(panes.first, Rect::new(0.0, 0.0, 130.0, 100.0));
+        assert_eq!(panes.second, Rect::new(136.0, 0.0, 70.0, 100.0));
+        assert_eq!(output.cursor, CursorIcon::ResizeHorizontal);
+
+        // The second pane can't get smaller than its minimum size
+        Script::new()
+            .move_to(133.0, 50.0)
+            .drag_to(203.0, 50.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut panes));
+        assert_eq!(panes.first.w, 160.0);
+
+        Script::new()
+            .move_to(163.0, 50.0)
+            .click(163.0, 50.0)
+            .click(163.0, 50.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut panes));
+        assert_eq!(panes.collapsed, Some(SplitterPane::First));
+        assert_eq!(panes.first.w, 0.0);
+        assert_eq!(panes.second, Rect::new(6.0, 0.0, 200.0, 100.0));
+
+        let output = Script::new()
+            .move_to(3.0, 50.0)
+            .click(3.0, 50.0)
+            .click(3.0, 50.0)
+            .move_to(300.0, 50.0)
+            .run(&mut ugui, |ugui| frame(ugui, &mut panes));
+        assert_eq!(panes.collapsed, None);
+        assert_eq!(panes.first.w, 160.0);
+        assert_eq!(output.cursor, CursorIcon::Default);
+    }
 }